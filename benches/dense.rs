@@ -0,0 +1,65 @@
+//! Compares running a dfa through it's hash map of transitions
+//! with running the same dfa compiled in to a dense table.
+//!
+//! Run with `cargo bench --bench dense`.
+
+use fmsi::Dfa;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 20;
+
+/// Runs the given closure a number of times and returns the fastest run
+fn measure<F: FnMut() -> bool>(mut run: F) -> Duration {
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(run());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, input: &str, elapsed: Duration) {
+    let chars = input.chars().count() as f64;
+    println!(
+        "{:<32} {:>10.2?} {:>8.2} ns/char",
+        name,
+        elapsed,
+        elapsed.as_nanos() as f64 / chars
+    );
+}
+
+fn bench(expression: &str, input: &str) {
+    let dfa = Dfa::from(expression).unwrap();
+    let dense = dfa.compile();
+    assert_eq!(dfa.run(input).unwrap_or(false), dense.run(input));
+
+    println!(
+        "{} ({} states, {} classes)",
+        expression,
+        dense.num_states(),
+        dense.num_classes()
+    );
+    report(
+        "  hash map",
+        input,
+        measure(|| dfa.run(black_box(input)).unwrap_or(false)),
+    );
+    report("  dense", input, measure(|| dense.run(black_box(input))));
+}
+
+fn main() {
+    let ab: String = (0..1_000_000)
+        .map(|i| if i % 3 == 0 { 'b' } else { 'a' })
+        .collect();
+    bench("(a|b)*abb(a|b)*", &ab);
+    bench("a|(ab|b)*", &"abb".repeat(333_333));
+
+    let words = "the quick brown fox jumps over the lazy dog ".repeat(20_000);
+    bench(
+        "(a|b|c|d|e|f|g|h|i|j|k|l|m|n|o|p|q|r|s|t|u|v|w|x|y|z| )*",
+        &words,
+    );
+}
@@ -0,0 +1,65 @@
+//! Measures the subset construction on regexes whose dfa
+//! grows exponentially, like `(a|b)*a(a|b){n}`, and compares it with
+//! a straightforward construction on sorted vectors of states.
+//!
+//! Run with `cargo bench --bench determinize`.
+
+use fmsi::nfa::{AlphabetIter, Nfa};
+use fmsi::util::{set_epsilon_clojure, set_transitions, state_epsilon_clojure};
+use std::collections::VecDeque;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Largest repetition the vector based construction is run for
+const MAX_NAIVE_REPETITION: usize = 10;
+
+/// Runs the given closure and returns the time it took and it's result
+fn measure<T, F: FnOnce() -> T>(run: F) -> (Duration, T) {
+    let start = Instant::now();
+    let result = black_box(run());
+    (start.elapsed(), result)
+}
+
+/// Subset construction that keeps the discovered sets in a vector
+/// and returns the number of dfa states
+fn naive_subset_construction(nfa: &Nfa) -> usize {
+    let alphabet: Vec<char> = nfa.alphabet_iter().copied().collect();
+    let mut states = vec![state_epsilon_clojure(nfa, nfa.start_state())];
+    let mut queue: VecDeque<Vec<u32>> = VecDeque::from([states[0].clone()]);
+
+    while let Some(current) = queue.pop_front() {
+        for &symbol in alphabet.iter() {
+            let next = set_transitions(nfa, &current, symbol);
+            let next = set_epsilon_clojure(nfa, &next);
+
+            if !states.contains(&next) {
+                queue.push_back(next.clone());
+                states.push(next);
+            }
+        }
+    }
+
+    states.len()
+}
+
+fn main() {
+    for repetition in [5, 10, 12, 15] {
+        let expression = format!("(a|b)*a{}", "(a|b)".repeat(repetition));
+        let nfa = Nfa::from(&expression).unwrap();
+
+        let (elapsed, dfa) = measure(|| nfa.to_dfa());
+        println!(
+            "(a|b)*a(a|b){{{}}}: {} nfa states, {} dfa states",
+            repetition,
+            nfa.num_states(),
+            dfa.num_states()
+        );
+        println!("  {:<12} {:>12.2?}", "bitsets", elapsed);
+
+        if repetition <= MAX_NAIVE_REPETITION {
+            let (elapsed, num_states) = measure(|| naive_subset_construction(&nfa));
+            assert_eq!(dfa.num_states(), num_states);
+            println!("  {:<12} {:>12.2?}", "vectors", elapsed);
+        }
+    }
+}
@@ -1,4 +1,12 @@
-mod traits;
+pub mod traits;
 pub mod iters;
+pub mod bytes;
 pub mod dfa;
 pub mod nfa;
+pub mod lazy_dfa;
+pub mod utf8;
+mod att;
+pub mod dot;
+pub mod error;
+pub mod jflap;
+mod table;
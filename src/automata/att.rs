@@ -0,0 +1,307 @@
+//! Reading and writing of acceptors in the AT&T text format used by
+//! OpenFst, with a line `source target label [weight]` for every
+//! transition and a line `state [weight]` for every final state.
+//!
+//! The source of the first line is the start state. Like in OpenFst,
+//! labels are numbers when there is no symbol table, and symbol names
+//! when there is one. Numbers are the Unicode code points of the
+//! symbols, with 0 for the empty string. Symbol names are single
+//! symbols, with `<eps>` for the empty string and `<space>` for the
+//! space char. States are renumbered when read: the start state becomes
+//! state 0, and the others follow in the order of their numbers.
+//! Weights are ignored, apart from infinite weights, which OpenFst
+//! uses for transitions that don't exist and states that are not final.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+
+const EPSILON: &str = "<eps>";
+const SPACE: &str = "<space>";
+
+/// An acceptor read from, or about to be written in the AT&T format,
+/// with the states numbered from 0 and state 0 as the start state
+#[derive(Debug, Default)]
+pub(crate) struct Att {
+    pub(crate) num_states: u32,
+    pub(crate) accept_states: Vec<u32>,
+    pub(crate) transitions: Vec<(u32, Option<char>, u32)>,
+}
+
+/// Returns the symbol table of the given alphabet,
+/// with `<eps>` as 0 and the symbols numbered from 1
+pub(crate) fn symbol_table(alphabet: &[char]) -> String {
+    let mut table = format!("{} 0\n", EPSILON);
+    for (index, &symbol) in alphabet.iter().enumerate() {
+        writeln!(table, "{} {}", name(Some(symbol)), index + 1).unwrap();
+    }
+    table
+}
+
+/// Returns the name of the symbol in a symbol table
+fn name(symbol: Option<char>) -> String {
+    match symbol {
+        None => EPSILON.to_string(),
+        Some(' ') => SPACE.to_string(),
+        Some(symbol) => symbol.to_string(),
+    }
+}
+
+fn parse_name(name: &str) -> Result<Option<char>, &'static str> {
+    match name {
+        EPSILON => Ok(None),
+        SPACE => Ok(Some(' ')),
+        name => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(symbol), None) => Ok(Some(symbol)),
+                _ => Err("Symbol names must be a single symbol"),
+            }
+        }
+    }
+}
+
+/// Returns the code point of the symbol, with 0 for the empty string
+fn code_point(symbol: Option<char>) -> u32 {
+    symbol.map_or(0, |symbol| symbol as u32)
+}
+
+fn parse_code_point(label: &str) -> Result<Option<char>, &'static str> {
+    let label: u32 = label
+        .parse()
+        .map_err(|_| "AT&T labels must be numbers when there is no symbol table")?;
+
+    match label {
+        0 => Ok(None),
+        label => char::from_u32(label)
+            .map(Some)
+            .ok_or("AT&T label is not a Unicode code point"),
+    }
+}
+
+/// Parses a symbol table with a `name number` line for every symbol
+/// and returns the names in it
+fn parse_symbol_table(table: &str) -> Result<HashSet<&str>, &'static str> {
+    let mut names = HashSet::new();
+    let mut numbers = HashSet::new();
+
+    for line in table.lines().filter(|line| !line.trim().is_empty()) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [name, number] = fields[..] else {
+            return Err("Symbol table lines must have a name and a number");
+        };
+        let number: u64 = number
+            .parse()
+            .map_err(|_| "Symbol table numbers must be numbers")?;
+
+        parse_name(name)?;
+        if !names.insert(name) || !numbers.insert(number) {
+            return Err("Symbol table has a name or a number more than once");
+        }
+    }
+
+    Ok(names)
+}
+
+fn is_infinite(weight: &str) -> Result<bool, &'static str> {
+    let weight: f64 = weight.parse().map_err(|_| "AT&T weights must be numbers")?;
+    Ok(weight.is_infinite())
+}
+
+impl Att {
+    /// Returns the symbols used in the transitions, sorted
+    pub(crate) fn alphabet(&self) -> Vec<char> {
+        let mut alphabet: Vec<char> = self
+            .transitions
+            .iter()
+            .filter_map(|(_, symbol, _)| *symbol)
+            .collect();
+        alphabet.sort();
+        alphabet.dedup();
+        alphabet
+    }
+
+    /// Parses an acceptor, with symbol names as labels if a
+    /// symbol table is given and code points otherwise
+    pub(crate) fn parse(input: &str, symbol_table: Option<&str>) -> Result<Self, &'static str> {
+        let names = symbol_table.map(parse_symbol_table).transpose()?;
+        let symbol = |label: &str| match &names {
+            Some(names) if names.contains(label) => parse_name(label),
+            Some(_) => Err("AT&T label is not in the symbol table"),
+            None => parse_code_point(label),
+        };
+        let state = |state: &str| -> Result<u32, &'static str> {
+            state.parse().map_err(|_| "AT&T states must be numbers")
+        };
+
+        let mut start = None;
+        let mut finals = Vec::new();
+        let mut arcs = Vec::new();
+
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            // the fourth field is either the output label of an acceptor
+            // written as a transducer, or a weight
+            let (source, target, input, weight) = match fields[..] {
+                [final_state] | [final_state, _] => {
+                    let final_state = state(final_state)?;
+                    start.get_or_insert(final_state);
+                    if fields.len() == 1 || !is_infinite(fields[1])? {
+                        finals.push(final_state);
+                    }
+                    continue;
+                }
+                [source, target, input] => (source, target, input, None),
+                [source, target, input, output] if input == output => (source, target, input, None),
+                [source, target, input, weight] => {
+                    if weight.parse::<f64>().is_err() {
+                        return Err("Only AT&T acceptors are supported");
+                    }
+                    (source, target, input, Some(weight))
+                }
+                [source, target, input, output, weight] => {
+                    if input != output {
+                        return Err("Only AT&T acceptors are supported");
+                    }
+                    (source, target, input, Some(weight))
+                }
+                _ => return Err("AT&T lines must have between 1 and 5 fields"),
+            };
+
+            let source = state(source)?;
+            let (symbol, target) = (symbol(input)?, state(target)?);
+            start.get_or_insert(source);
+            if !weight.map(is_infinite).transpose()?.unwrap_or(false) {
+                arcs.push((source, symbol, target));
+            }
+        }
+
+        let Some(start) = start else {
+            return Ok(Att {
+                num_states: 1,
+                ..Att::default()
+            });
+        };
+
+        let mut ids: Vec<u32> = arcs
+            .iter()
+            .flat_map(|&(source, _, target)| [source, target])
+            .chain(finals.iter().copied())
+            .collect();
+        ids.sort();
+        ids.dedup();
+
+        let mut lookup_table: BTreeMap<u32, u32> = BTreeMap::from([(start, 0)]);
+        for &id in ids.iter().filter(|&&id| id != start) {
+            lookup_table.insert(id, lookup_table.len() as u32);
+        }
+
+        let mut accept_states: Vec<u32> = finals.iter().map(|state| lookup_table[state]).collect();
+        accept_states.sort();
+        accept_states.dedup();
+
+        Ok(Att {
+            num_states: lookup_table.len() as u32,
+            accept_states,
+            transitions: arcs
+                .into_iter()
+                .map(|(source, symbol, target)| {
+                    (lookup_table[&source], symbol, lookup_table[&target])
+                })
+                .collect(),
+        })
+    }
+
+    /// Writes the acceptor with symbol names as labels if a symbol table
+    /// is given and code points otherwise, the transitions of the start
+    /// state first. If the start state has no transitions, the other
+    /// states can't be reached and are left out.
+    pub(crate) fn to_text(&self, symbol_table: Option<&str>) -> Result<String, &'static str> {
+        let names = symbol_table.map(parse_symbol_table).transpose()?;
+        let label = |symbol: Option<char>| match &names {
+            Some(names) if names.contains(name(symbol).as_str()) => Ok(name(symbol)),
+            Some(_) => Err("The symbol table is missing a symbol"),
+            None => Ok(code_point(symbol).to_string()),
+        };
+        let mut text = String::new();
+
+        let mut transitions = self.transitions.clone();
+        transitions.sort();
+        if transitions
+            .first()
+            .is_none_or(|&(source, _, _)| source != 0)
+        {
+            if self.accept_states.contains(&0) {
+                text.push_str("0\n");
+            }
+            return Ok(text);
+        }
+
+        for (source, symbol, target) in transitions {
+            writeln!(text, "{}\t{}\t{}", source, target, label(symbol)?).unwrap();
+        }
+        for state in self.accept_states.iter() {
+            writeln!(text, "{}", state).unwrap();
+        }
+
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn att_parse() {
+        let input = "3 5 97 0.5\n5 3 0\n3 3 32 32\n3 5 98 Infinity\n5 1.25\n3 Infinity\n";
+        let att = Att::parse(input, None).unwrap();
+
+        assert_eq!(2, att.num_states);
+        assert_eq!(vec![1], att.accept_states);
+        assert_eq!(
+            vec![(0, Some('a'), 1), (1, None, 0), (0, Some(' '), 0)],
+            att.transitions
+        );
+        assert_eq!(vec![' ', 'a'], att.alphabet());
+
+        let text = att.to_text(None).unwrap();
+        assert_eq!("0\t0\t32\n0\t1\t97\n1\t0\t0\n1\n", text);
+        let written = Att::parse(&text, None).unwrap();
+        assert_eq!(att.accept_states, written.accept_states);
+        assert_eq!(3, written.transitions.len());
+    }
+
+    #[test]
+    fn att_parse_with_symbol_table() {
+        let table = symbol_table(&[' ', 'a', 'b']);
+        assert_eq!("<eps> 0\n<space> 1\na 2\nb 3\n", table);
+
+        let att = Att::parse("0 1 b b\n1 0 <eps>\n1 1 <space>\n1\n", Some(&table)).unwrap();
+        assert_eq!(
+            vec![(0, Some('b'), 1), (1, None, 0), (1, Some(' '), 1)],
+            att.transitions
+        );
+
+        let text = att.to_text(Some(&table)).unwrap();
+        assert_eq!("0\t1\tb\n1\t0\t<eps>\n1\t1\t<space>\n1\n", text);
+        assert!(att.to_text(Some("<eps> 0\na 1\n")).is_err());
+
+        assert!(Att::parse("0 1 c\n", Some(&table)).is_err());
+        assert!(Att::parse("0 1 1\n", Some(&table)).is_err());
+        assert!(Att::parse("0 1 a\n", Some("a 1\na 2\n")).is_err());
+        assert!(Att::parse("0 1 a\n", Some("ab 1\n")).is_err());
+    }
+
+    #[test]
+    fn att_parse_errors() {
+        assert!(Att::parse("0 1 a\n", None).is_err());
+        assert!(Att::parse("0 1 1114112\n", None).is_err());
+        assert!(Att::parse("0 1 97 98\n1\n", None).is_ok());
+        assert!(Att::parse("0 1 97 b\n", None).is_err());
+        assert!(Att::parse("0 x 97\n", None).is_err());
+        assert!(Att::parse("0 1 97 98 1 2\n", None).is_err());
+        assert!(Att::parse("0 1 97 98 1\n", None).is_err());
+        assert_eq!(1, Att::parse("", None).unwrap().num_states);
+    }
+}
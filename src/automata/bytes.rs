@@ -0,0 +1,422 @@
+use crate::automata::traits::*;
+use crate::automata::utf8::Utf8Sequences;
+use crate::language::Regex;
+use crate::nfa::Nfa;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// A single state of a byte nfa
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ByteState {
+    /// Transitions on every byte inside of an inclusive range
+    ranges: Vec<(u8, u8, u32)>,
+    epsilons: Vec<u32>,
+}
+
+/// A nfa over bytes instead of chars.
+///
+/// Char classes are lowered to UTF-8 byte sequences,
+/// so a class with a huge range of chars only takes up a few
+/// states and the nfa can run directly on `&[u8]`.
+///
+/// # Example
+/// ```rust
+/// use fmsi::automata::bytes::ByteNfa;
+///
+/// let nfa = ByteNfa::from("[α-ω]*").unwrap();
+///
+/// assert!(nfa.run("λαμβδα".as_bytes()));
+/// assert!(!nfa.run("λάμβδα".as_bytes()));
+/// assert!(!nfa.run(b"\xCE"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteNfa {
+    states: Vec<ByteState>,
+    start_state: u32,
+    accept_states: Vec<u32>,
+}
+
+impl ByteNfa {
+    pub fn from(expression: &str) -> Result<Self, &'static str> {
+        Ok(ByteNfa::from_regex(&Regex::parse(expression)?))
+    }
+
+    /// Uses the Thompson construction to build a byte nfa
+    /// from a given syntax tree
+    pub fn from_regex(regex: &Regex) -> Self {
+        let mut nfa = ByteNfa {
+            states: Vec::new(),
+            start_state: 0,
+            accept_states: Vec::new(),
+        };
+
+        let (start, end) = nfa.compile(regex);
+        nfa.start_state = start;
+        nfa.accept_states.push(end);
+
+        nfa
+    }
+
+    #[inline]
+    pub fn start_state(&self) -> u32 {
+        self.start_state
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.states.len()
+    }
+
+    fn is_accept_state(&self, state: u32) -> bool {
+        self.accept_states.contains(&state)
+    }
+
+    fn add_state(&mut self) -> u32 {
+        self.states.push(ByteState::default());
+        (self.states.len() - 1) as u32
+    }
+
+    fn add_epsilon(&mut self, source: u32, target: u32) {
+        self.states[source as usize].epsilons.push(target);
+    }
+
+    /// Compiles the regex in to a fragment of the nfa and
+    /// returns it's start and end states.
+    fn compile(&mut self, regex: &Regex) -> (u32, u32) {
+        match regex {
+            Regex::EmptySet => (self.add_state(), self.add_state()),
+            Regex::EmptyString => {
+                let start = self.add_state();
+                let end = self.add_state();
+                self.add_epsilon(start, end);
+                (start, end)
+            }
+            Regex::Symbol(symbol) => self.compile_class(&[(*symbol, *symbol)]),
+            Regex::Class(ranges) => self.compile_class(ranges),
+            Regex::Concat(first, second) => {
+                let (first_start, first_end) = self.compile(first);
+                let (second_start, second_end) = self.compile(second);
+                self.add_epsilon(first_end, second_start);
+                (first_start, second_end)
+            }
+            Regex::Union(first, second) => {
+                let start = self.add_state();
+                let (first_start, first_end) = self.compile(first);
+                let (second_start, second_end) = self.compile(second);
+                let end = self.add_state();
+
+                self.add_epsilon(start, first_start);
+                self.add_epsilon(start, second_start);
+                self.add_epsilon(first_end, end);
+                self.add_epsilon(second_end, end);
+                (start, end)
+            }
+            Regex::KleeneStar(inner) => {
+                let start = self.add_state();
+                let (inner_start, inner_end) = self.compile(inner);
+                let end = self.add_state();
+
+                self.add_epsilon(start, inner_start);
+                self.add_epsilon(start, end);
+                self.add_epsilon(inner_end, inner_start);
+                self.add_epsilon(inner_end, end);
+                (start, end)
+            }
+        }
+    }
+
+    /// Lowers every range of chars to the UTF-8 sequences
+    /// that match it and builds a chain of states for each of them
+    fn compile_class(&mut self, ranges: &[(char, char)]) -> (u32, u32) {
+        let start = self.add_state();
+        let end = self.add_state();
+
+        for &(first, last) in ranges {
+            for sequence in Utf8Sequences::new(first, last) {
+                let mut current = start;
+                let bytes = sequence.as_slice();
+
+                for (index, range) in bytes.iter().enumerate() {
+                    let next = if index + 1 == bytes.len() {
+                        end
+                    } else {
+                        self.add_state()
+                    };
+
+                    self.states[current as usize]
+                        .ranges
+                        .push((range.start, range.end, next));
+                    current = next;
+                }
+            }
+        }
+
+        (start, end)
+    }
+
+    /// Calculates the epsilon clojure for a given set of states
+    fn epsilon_clojure(&self, set: &[u32]) -> Vec<u32> {
+        let mut clojure: Vec<u32> = set.to_vec();
+        let mut visited = vec![false; self.states.len()];
+        let mut queue: VecDeque<u32> = set.iter().cloned().collect();
+
+        set.iter().for_each(|&state| visited[state as usize] = true);
+
+        while let Some(current) = queue.pop_front() {
+            for &state in self.states[current as usize].epsilons.iter() {
+                if !visited[state as usize] {
+                    visited[state as usize] = true;
+                    clojure.push(state);
+                    queue.push_back(state);
+                }
+            }
+        }
+
+        clojure.sort();
+        clojure
+    }
+
+    /// Returns the epsilon clojure of the set the given set
+    /// transitions to for the given byte
+    fn step(&self, set: &[u32], byte: u8) -> Vec<u32> {
+        let mut target: Vec<u32> = Vec::new();
+
+        for &state in set {
+            for &(start, end, next) in self.states[state as usize].ranges.iter() {
+                if start <= byte && byte <= end && !target.contains(&next) {
+                    target.push(next);
+                }
+            }
+        }
+
+        self.epsilon_clojure(&target)
+    }
+
+    /// Processes the given bytes by simulating the nfa and
+    /// returns true if the final set of states contains an accept state.
+    pub fn run(&self, input: &[u8]) -> bool {
+        let mut current = self.epsilon_clojure(&[self.start_state]);
+
+        for &byte in input {
+            if current.is_empty() {
+                return false;
+            }
+            current = self.step(&current, byte);
+        }
+
+        current.iter().any(|&state| self.is_accept_state(state))
+    }
+
+    /// Converts the nfa to a dfa using the subset construction
+    pub fn to_dfa(&self) -> ByteDfa {
+        let mut states: Vec<Vec<u32>> = vec![self.epsilon_clojure(&[self.start_state])];
+        let mut lookup_table: HashMap<Vec<u32>, u32> = HashMap::new();
+        let mut queue: VecDeque<u32> = VecDeque::new();
+        let mut dfa = ByteDfa {
+            transition_table: Vec::new(),
+            accept_states: Vec::new(),
+        };
+
+        lookup_table.insert(states[0].clone(), 0);
+        queue.push_back(0);
+
+        while let Some(current) = queue.pop_front() {
+            let mut row = [0u32; 256];
+
+            for byte in 0..=255u8 {
+                let new_set = self.step(&states[current as usize], byte);
+
+                row[byte as usize] = match lookup_table.get(&new_set) {
+                    Some(&state) => state,
+                    None => {
+                        let state = states.len() as u32;
+                        lookup_table.insert(new_set.clone(), state);
+                        states.push(new_set);
+                        queue.push_back(state);
+                        state
+                    }
+                };
+            }
+
+            // rows are added in the same order the states were discovered in
+            dfa.transition_table.extend_from_slice(&row);
+        }
+
+        dfa.accept_states = states
+            .iter()
+            .map(|set| set.iter().any(|&state| self.is_accept_state(state)))
+            .collect();
+
+        dfa
+    }
+
+    /// Converts the byte nfa to a generic nfa over `u8`
+    /// by expanding every range in to a transition for each of it's bytes.
+    /// The alphabet only contains the bytes that have a transition.
+    pub fn to_nfa(&self) -> Nfa<u8> {
+        let mut nfa: Nfa<u8> = Nfa::new();
+
+        // the start state is always the first one added by the construction
+        debug_assert_eq!(0, self.start_state);
+
+        (1..self.states.len()).for_each(|_| nfa.add_state());
+        self.accept_states
+            .iter()
+            .for_each(|&state| nfa.add_accept_state(state));
+
+        let alphabet: BTreeSet<u8> = self
+            .states
+            .iter()
+            .flat_map(|state| state.ranges.iter())
+            .flat_map(|&(start, end, _)| start..=end)
+            .collect();
+        alphabet.into_iter().for_each(|byte| nfa.add_symbol(byte));
+
+        for (source, state) in self.states.iter().enumerate() {
+            for &(start, end, target) in state.ranges.iter() {
+                for byte in start..=end {
+                    nfa.add_transition(&(source as u32, Some(byte)), target)
+                        .unwrap();
+                }
+            }
+            for &target in state.epsilons.iter() {
+                nfa.add_transition(&(source as u32, None), target).unwrap();
+            }
+        }
+
+        nfa
+    }
+}
+
+/// A complete dfa over bytes, stored as a table with
+/// a row of 256 target states for every state.
+/// The start state is always 0.
+///
+/// # Example
+/// ```rust
+/// use fmsi::automata::bytes::ByteDfa;
+///
+/// let dfa = ByteDfa::from(".*č").unwrap();
+///
+/// assert!(dfa.run("abč".as_bytes()));
+/// assert!(dfa.run(b"\x00\xC4\x8D"));
+/// assert!(!dfa.run(b"\xFF\xC4\x8D"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteDfa {
+    transition_table: Vec<u32>,
+    accept_states: Vec<bool>,
+}
+
+impl ByteDfa {
+    /// Constructor returns a byte dfa from a given regex
+    pub fn from(expression: &str) -> Result<Self, &'static str> {
+        Ok(ByteNfa::from(expression)?.to_dfa())
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.accept_states.len()
+    }
+
+    #[inline]
+    pub fn next_state(&self, state: u32, byte: u8) -> u32 {
+        self.transition_table[state as usize * 256 + byte as usize]
+    }
+
+    #[inline]
+    pub fn is_accept_state(&self, state: u32) -> bool {
+        self.accept_states[state as usize]
+    }
+
+    /// Processes the given bytes and returns true
+    /// if they end up in an accept state.
+    pub fn run(&self, input: &[u8]) -> bool {
+        let state = input
+            .iter()
+            .fold(0, |state, &byte| self.next_state(state, byte));
+
+        self.is_accept_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_nfa_run() {
+        let nfa = ByteNfa::from("a|(ab|b)*").unwrap();
+
+        assert!(nfa.run(b""));
+        assert!(nfa.run(b"a"));
+        assert!(nfa.run(b"abbab"));
+        assert!(!nfa.run(b"aa"));
+        assert!(!nfa.run(b"ba"));
+    }
+
+    #[test]
+    fn byte_nfa_unicode_class() {
+        // the whole range only needs a handful of states
+        let nfa = ByteNfa::from("[^a]").unwrap();
+        assert!(nfa.num_states() <= 32);
+
+        assert!(nfa.run("b".as_bytes()));
+        assert!(nfa.run("ž".as_bytes()));
+        assert!(nfa.run("😀".as_bytes()));
+        assert!(!nfa.run("a".as_bytes()));
+        assert!(!nfa.run("bb".as_bytes()));
+
+        // invalid UTF-8 isn't a char
+        assert!(!nfa.run(b"\xFF"));
+        assert!(!nfa.run(b"\xED\xA0\x80"));
+    }
+
+    #[test]
+    fn byte_dfa_agrees_with_nfa() {
+        let inputs: [&[u8]; 8] = [
+            b"",
+            b"a",
+            b"ab",
+            b"a\xC5\xBEb",
+            "ažžc".as_bytes(),
+            b"\xC5",
+            b"\xC5\xBE",
+            b"abc\xFF",
+        ];
+
+        for expression in ["a[b-ž]*", "(a|ž)*c", ".*", "a.b"] {
+            let nfa = ByteNfa::from(expression).unwrap();
+            let dfa = nfa.to_dfa();
+
+            for input in inputs {
+                assert_eq!(nfa.run(input), dfa.run(input), "{} {:?}", expression, input);
+            }
+        }
+    }
+
+    #[test]
+    fn byte_nfa_to_nfa() {
+        let byte_nfa = ByteNfa::from("(a|ž)*c").unwrap();
+        let nfa = byte_nfa.to_nfa();
+        let mut dfa = nfa.to_dfa();
+        dfa.minimize().unwrap();
+
+        for input in ["", "c", "ac", "žac", "ažžc", "a", "ca"] {
+            let input = input.as_bytes();
+            assert_eq!(byte_nfa.run(input), nfa.run_symbols(input.iter().copied()));
+            assert_eq!(
+                byte_nfa.run(input),
+                dfa.run_symbols(input.iter().copied()).unwrap_or(false)
+            );
+        }
+    }
+
+    #[test]
+    fn byte_dfa_run() {
+        let dfa = ByteDfa::from("[0-9][0-9]*(\\.[0-9]*|())").unwrap();
+
+        assert!(dfa.run(b"3"));
+        assert!(dfa.run(b"3.14"));
+        assert!(dfa.run(b"42."));
+        assert!(!dfa.run(b".5"));
+        assert!(!dfa.run(b"4\x002"));
+    }
+}
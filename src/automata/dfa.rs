@@ -1,31 +1,61 @@
+use crate::automata::dot::{DotOptions, Graph};
+use crate::automata::table::{self, Table};
 use crate::automata::traits::*;
+use crate::language::Symbol;
 use crate::nfa::Nfa;
-use std::collections::HashMap;
+use crate::util;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::default::Default;
+use std::fmt;
+use std::sync::OnceLock;
+
+pub use dense::{Dense, DenseDfa, DenseDfaRef};
+pub use matcher::DfaMatcher;
+pub use search::{Match, Matches, Split};
+mod att;
+pub mod dense;
+pub mod derivative;
+mod jflap;
+pub mod matcher;
 pub mod regex;
+#[cfg(feature = "serde")]
+mod schema;
+pub mod search;
 // mod conversion;
 
+/// A deterministic finite automata over symbols of type `S`
+///
+/// With the `serde` feature, a dfa is serialized as a map of
+/// `states` (consecutive numbers), `alphabet`, `transitions` (a list
+/// of `{ from, symbol, to }`), `start` (the first state) and `accepts`.
+/// Deserialization fails if the parts don't form a valid dfa,
+/// like when a state has two transitions on the same symbol.
 #[derive(Debug, Clone)]
-pub struct Dfa {
+pub struct Dfa<S = char> {
     states: Vec<u32>,
-    alphabet: Vec<char>,
-    transition_fn: HashMap<(u32, char), u32>,
+    alphabet: Vec<S>,
+    transition_fn: HashMap<(u32, S), u32>,
     accept_states: Vec<u32>,
+    /// States from which an accept state can be reached, computed
+    /// when searching first needs them and cleared on every change
+    live: OnceLock<Vec<bool>>,
 }
 
-impl Default for Dfa {
+impl<S: Symbol> Default for Dfa<S> {
     fn default() -> Self {
         Dfa {
             states: vec![0],
             alphabet: Vec::new(),
             transition_fn: HashMap::new(),
             accept_states: Vec::new(),
+            live: OnceLock::new(),
         }
     }
 }
 
-impl State for Dfa {
+impl<S: Symbol> State for Dfa<S> {
     fn add_state(&mut self) {
+        self.live.take();
         self.states.push(self.states.last().unwrap() + 1)
     }
 
@@ -33,6 +63,7 @@ impl State for Dfa {
         if self.states.len() == 1 {
             return;
         }
+        self.live.take();
 
         let target = self.states.pop().unwrap();
 
@@ -47,6 +78,7 @@ impl State for Dfa {
     }
 
     fn add_accept_state(&mut self, state: u32) {
+        self.live.take();
         if self.states.contains(&state) {
             self.accept_states.push(state);
             self.accept_states.sort();
@@ -54,31 +86,37 @@ impl State for Dfa {
     }
 
     fn remove_accept_state(&mut self, state: u32) {
+        self.live.take();
         if let Some(index) = self.accept_states.iter().position(|&e| e == state) {
             self.accept_states.remove(index);
         }
     }
 }
 
-impl Alphabet for Dfa {
-    fn add_symbol(&mut self, symbol: char) {
+impl<S: Symbol> Alphabet for Dfa<S> {
+    type Symbol = S;
+
+    fn add_symbol(&mut self, symbol: S) {
         if !self.alphabet.contains(&symbol) {
             self.alphabet.push(symbol);
         }
     }
 
-    fn remove_symbol(&mut self, symbol: char) {
+    fn remove_symbol(&mut self, symbol: S) {
+        self.live.take();
         if let Some(position) = self.alphabet.iter().position(|e| *e == symbol) {
             self.alphabet.remove(position);
         }
 
         self.transition_fn
-            .retain(|&(_, transition_symbol), _| transition_symbol != symbol);
+            .retain(|(_, transition_symbol), _| *transition_symbol != symbol);
     }
 }
 
-impl Transition for Dfa {
-    fn add_transition(&mut self, source: &(u32, char), target: u32) -> Result<(), &'static str> {
+impl<S: Symbol> Transition for Dfa<S> {
+    type Source = (u32, S);
+
+    fn add_transition(&mut self, source: &(u32, S), target: u32) -> Result<(), &'static str> {
         if !self.states.contains(&source.0) {
             return Err("The source state isn't a valid state");
         }
@@ -91,25 +129,22 @@ impl Transition for Dfa {
             return Err("The symbol is not in the alphabet");
         }
 
-        if self.transition_fn.get(source).is_some() {
+        if self.transition_fn.contains_key(source) {
             return Err("The transition already exists");
         }
 
-        self.transition_fn.insert(*source, target);
+        self.live.take();
+        self.transition_fn.insert(source.clone(), target);
 
         Ok(())
     }
     //
-    // fn remove_transition(&mut self, source: &(u32, char), target: u32) {
+    // fn remove_transition(&mut self, source: &(u32, S), target: u32) {
     //     self.transition_fn.remove(source);
     // }
 }
 
 impl Dfa {
-    pub fn new() -> Self {
-        Default::default()
-    }
-
     /// Constructor returns a minimized dfa from a given regex
     pub fn from(regex: &str) -> Result<Self, &'static str> {
         let mut dfa = Nfa::from(regex)?.to_dfa();
@@ -117,6 +152,89 @@ impl Dfa {
         Ok(dfa)
     }
 
+    /// Processes the given string and returns Ok(true) if it
+    /// ends up in an accept state.
+    pub fn run(&self, input: &str) -> Result<bool, &'static str> {
+        self.run_symbols(input.chars())
+    }
+
+    pub fn to_regex(&self) -> String {
+        regex::get_regex(self).to_string()
+    }
+
+    /// Reads a dfa from a plain text transition table,
+    /// in the format written by it's `Display` implementation
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from_table(
+    ///     "      | a  | b
+    ///      ->q0  | q1 | ∅
+    ///      *q1   | q1 | q0",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(dfa.run("aba").unwrap());
+    /// assert_eq!(dfa.to_string(), Dfa::from_table(&dfa.to_string()).unwrap().to_string());
+    /// ```
+    pub fn from_table(input: &str) -> Result<Self, &'static str> {
+        let table = Table::parse(input)?;
+
+        let mut dfa = Dfa::new();
+        (1..table.num_states).for_each(|_| dfa.add_state());
+        table
+            .alphabet
+            .into_iter()
+            .for_each(|symbol| dfa.add_symbol(symbol));
+        table
+            .accept_states
+            .into_iter()
+            .for_each(|state| dfa.add_accept_state(state));
+
+        for (source, symbol, target) in table.transitions {
+            let symbol = symbol.ok_or("A dfa can't have transitions on the empty string")?;
+            if dfa.transition_fn.contains_key(&(source, symbol)) {
+                return Err("A dfa can't have more than one target for a symbol");
+            }
+            dfa.add_transition(&(source, symbol), target)?;
+        }
+
+        Ok(dfa)
+    }
+}
+
+impl<S: Symbol> Dfa<S> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates a dfa from it's parts without checking them
+    pub(crate) fn from_parts(
+        states: Vec<u32>,
+        alphabet: Vec<S>,
+        transition_fn: HashMap<(u32, S), u32>,
+        accept_states: Vec<u32>,
+    ) -> Self {
+        Dfa {
+            states,
+            alphabet,
+            transition_fn,
+            accept_states,
+            live: OnceLock::new(),
+        }
+    }
+
+    #[inline]
+    pub fn start_state(&self) -> u32 {
+        *self.states.first().unwrap()
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.states.len()
+    }
+
     fn is_accept_state(&self, state: u32) -> bool {
         self.accept_states.contains(&state)
     }
@@ -125,7 +243,7 @@ impl Dfa {
     pub fn is_complete(&self) -> bool {
         for state in self.states.iter() {
             for symbol in &self.alphabet {
-                if !self.transition_fn.contains_key(&(*state, *symbol)) {
+                if !self.transition_fn.contains_key(&(*state, symbol.clone())) {
                     return false;
                 }
             }
@@ -134,25 +252,37 @@ impl Dfa {
         true
     }
 
-    /// Processes the given string and returns Ok(true) if it
-    /// ends up in an accept state.
-    pub fn run(&self, input: &str) -> Result<bool, &'static str> {
+    /// Processes the given sequence of symbols and returns Ok(true)
+    /// if it ends up in an accept state.
+    pub fn run_symbols<I: IntoIterator<Item = S>>(&self, input: I) -> Result<bool, &'static str> {
         let mut current_state = self.states.first().unwrap();
 
-        for c in input.chars() {
-            match self.transition_fn.get(&(*current_state, c)) {
+        for symbol in input {
+            match self.transition_fn.get(&(*current_state, symbol)) {
                 Some(state) => current_state = state,
                 None => return Err("The automata is incomplete"),
             }
-            current_state = self.transition_fn.get(&(*current_state, c)).unwrap();
         }
 
         Ok(self.accept_states.contains(current_state))
     }
 
-    /// Determines if the two sets are n-equivalent to each other.
+    /// Splits the alphabet in to classes of symbols that
+    /// transition every state to the same state.
+    /// Such symbols can't be told apart by the dfa.
+    pub fn symbol_classes(&self) -> Vec<Vec<S>> {
+        util::group_symbols(&self.alphabet, |symbol| {
+            self.states
+                .iter()
+                .map(|&state| self.transition_fn.get(&(state, symbol.clone())))
+                .collect::<Vec<Option<&u32>>>()
+        })
+    }
+
+    /// Determines if the two sets are n-equivalent to each other,
+    /// checking the transitions for the given symbols.
     /// Returns true if they are, and false if they are not.
-    fn are_equivalent(&self, sets: &[Vec<u32>], first: u32, second: u32) -> bool {
+    fn are_equivalent(&self, symbols: &[S], sets: &[Vec<u32>], first: u32, second: u32) -> bool {
         // first find the set indexes for the first and second state
         let first_id = sets.iter().position(|s| s.contains(&first)).unwrap();
         let second_id = sets.iter().position(|s| s.contains(&second)).unwrap();
@@ -161,15 +291,17 @@ impl Dfa {
             return false;
         }
 
-        for &symbol in self.alphabet.iter() {
-            let first_transition_id = sets
-                .iter()
-                .position(|set| set.contains(self.transition_fn.get(&(first, symbol)).unwrap()))
-                .unwrap();
-            let second_transition_id = sets
-                .iter()
-                .position(|set| set.contains(self.transition_fn.get(&(second, symbol)).unwrap()))
-                .unwrap();
+        // a missing transition goes to an implicit dead state, which is
+        // in none of the sets
+        let target_set = |state: u32, symbol: &S| {
+            self.transition_fn
+                .get(&(state, symbol.clone()))
+                .map(|target| sets.iter().position(|set| set.contains(target)).unwrap())
+        };
+
+        for symbol in symbols.iter() {
+            let first_transition_id = target_set(first, symbol);
+            let second_transition_id = target_set(second, symbol);
 
             if first_transition_id != second_transition_id {
                 return false;
@@ -192,6 +324,11 @@ impl Dfa {
         );
         let mut done = false;
 
+        // symbols in the same class behave the same,
+        // so only one of them has to be checked
+        let classes = self.symbol_classes();
+        let symbols: Vec<S> = classes.iter().map(|class| class[0].clone()).collect();
+
         while !done {
             let mut new_eqvl: Vec<Vec<u32>> = Vec::new();
             // if a modification gets done, set this to false
@@ -216,7 +353,7 @@ impl Dfa {
                         let comparison_state = set[i];
 
                         // if the states are equivalent
-                        if self.are_equivalent(&old_eqvl, comparison_state, current_state) {
+                        if self.are_equivalent(&symbols, &old_eqvl, comparison_state, current_state) {
                             // find the new equivalence set that contains the comparison state
                             // and add the current state to that set
                             new_eqvl
@@ -243,6 +380,16 @@ impl Dfa {
             old_eqvl = new_eqvl;
         }
 
+        // the set containing the start state has to become
+        // the first state of the new dfa
+        let start_state = self.start_state();
+        let start_set = old_eqvl
+            .iter()
+            .position(|set| set.contains(&start_state))
+            .unwrap();
+        let start_set = old_eqvl.remove(start_set);
+        old_eqvl.insert(0, start_set);
+
         let mut dfa = Dfa::new();
         dfa.alphabet = self.alphabet.clone();
 
@@ -264,35 +411,165 @@ impl Dfa {
         for set in &old_eqvl {
             let state = set.first().unwrap();
 
-            for &symbol in self.alphabet.iter() {
-                let dest = self.transition_fn.get(&(*state, symbol)).unwrap();
+            for class in classes.iter() {
+                let Some(dest) = self.transition_fn.get(&(*state, class[0].clone())) else {
+                    continue;
+                };
 
                 let dest_set = old_eqvl.iter().find(|set| set.contains(dest)).unwrap();
                 let dest_set = lookup_table.get(&dest_set).unwrap();
                 let source_set = lookup_table.get(&set).unwrap();
 
-                dfa.add_transition(&(*source_set, symbol), *dest_set)?;
+                for symbol in class.iter() {
+                    dfa.add_transition(&(*source_set, symbol.clone()), *dest_set)?;
+                }
             }
         }
         *self = dfa;
         Ok(())
     }
 
+    /// Returns true if both dfas accept the same language.
+    ///
+    /// Pairs of states reachable on the same input are explored together,
+    /// a missing transition leading to a state that rejects everything.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let first = Dfa::from("(a|b)*").unwrap();
+    ///
+    /// assert!(first.is_equivalent(&Dfa::from("(a*b*)*").unwrap()));
+    /// assert!(!first.is_equivalent(&Dfa::from("(ab)*").unwrap()));
+    /// ```
+    pub fn is_equivalent(&self, other: &Dfa<S>) -> bool {
+        let mut symbols: Vec<&S> = self.alphabet.iter().chain(other.alphabet.iter()).collect();
+        symbols.sort();
+        symbols.dedup();
+
+        let start = (Some(self.start_state()), Some(other.start_state()));
+        let mut visited: HashSet<(Option<u32>, Option<u32>)> = HashSet::from([start]);
+        let mut queue: VecDeque<(Option<u32>, Option<u32>)> = VecDeque::from([start]);
+
+        while let Some((first, second)) = queue.pop_front() {
+            let first_accepts = first.is_some_and(|state| self.is_accept_state(state));
+            let second_accepts = second.is_some_and(|state| other.is_accept_state(state));
+            if first_accepts != second_accepts {
+                return false;
+            }
+
+            for &symbol in symbols.iter() {
+                let step = |dfa: &Dfa<S>, state: Option<u32>| {
+                    state.and_then(|state| dfa.transition_fn.get(&(state, symbol.clone())).copied())
+                };
+                let next = (step(self, first), step(other, second));
+
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        true
+    }
+
     fn increment_states(&mut self, increment: u32) {
-        let mut lookup_table: HashMap<(u32, char), u32> = HashMap::new();
+        let mut lookup_table: HashMap<(u32, S), u32> = HashMap::new();
 
+        self.live.take();
         self.states.iter_mut().for_each(|e| *e += increment);
         self.accept_states.iter_mut().for_each(|e| *e += increment);
 
         self.transition_fn.iter().for_each(|((source, symbol), target)| {
-            lookup_table.insert((source + increment, *symbol), target + increment);
+            lookup_table.insert((source + increment, symbol.clone()), target + increment);
         });
 
         self.transition_fn = lookup_table;
     }
+}
 
-    pub fn to_regex(&self) -> String {
-        regex::get_regex(self).to_string()
+impl<S: Symbol + fmt::Display> Dfa<S> {
+    /// Returns the graph of the dfa
+    pub(crate) fn graph(&self) -> Graph {
+        let mut graph = Graph::new(&self.states, self.start_state(), &self.accept_states);
+
+        for ((source, symbol), target) in self.transition_fn.iter() {
+            graph.add_edge(*source, *target, Some(symbol.to_string()));
+        }
+
+        graph
+    }
+
+    /// Returns the dfa in the graphviz dot format, transitions
+    /// between the same states are merged in to one edge
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from("(a|b)c").unwrap();
+    /// let dot = dfa.to_dot();
+    ///
+    /// assert!(dot.contains("0 -> 3 [label=\"a, b\"];"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::default())
+    }
+
+    /// Returns the dfa in the graphviz dot format, with the given options
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        self.graph().to_dot(options)
+    }
+
+    /// Returns the dfa as a mermaid state diagram, for markdown documents
+    pub fn to_mermaid(&self) -> String {
+        self.graph().to_mermaid()
+    }
+
+    /// Returns the dfa as a tikz picture, for LaTeX documents.
+    /// The document needs `\usetikzlibrary{automata, arrows.meta}`.
+    pub fn to_tikz(&self) -> String {
+        self.graph().to_tikz()
+    }
+}
+
+impl<S: Symbol + fmt::Display> fmt::Display for Dfa<S> {
+    /// Writes the transition table of the dfa, with a row for every state
+    /// and a column for every symbol, in sorted order.
+    /// Missing entries are shown as `∅`, and the symbols
+    /// `ε` and `|` and whitespace are escaped like `\u{7c}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut alphabet = self.alphabet.clone();
+        alphabet.sort();
+
+        let mut header = vec![String::new()];
+        header.extend(
+            alphabet
+                .iter()
+                .map(|symbol| table::symbol_cell(&symbol.to_string())),
+        );
+
+        let mut states = self.states.clone();
+        states.sort();
+
+        let mut rows = vec![header];
+        for state in states {
+            let mut row = vec![table::row_label(
+                state,
+                state == self.start_state(),
+                self.is_accept_state(state),
+            )];
+            row.extend(alphabet.iter().map(|symbol| {
+                match self.transition_fn.get(&(state, symbol.clone())) {
+                    Some(target) => target.to_string(),
+                    None => table::EMPTY_CELL.to_string(),
+                }
+            }));
+            rows.push(row);
+        }
+
+        table::write_table(f, &rows)
     }
 }
 
@@ -305,20 +582,20 @@ mod tests {
         let dfa = Nfa::from("a|(ab|b)*").unwrap().to_dfa();
         let sets = vec![vec![0, 1, 2, 4], vec![3, 5]];
 
-        assert!(dfa.are_equivalent(&sets, 1, 2));
-        assert!(dfa.are_equivalent(&sets, 2, 4));
-        assert!(dfa.are_equivalent(&sets, 1, 4));
-
-        assert!(!dfa.are_equivalent(&sets, 0, 1));
-        assert!(!dfa.are_equivalent(&sets, 0, 2));
-        assert!(!dfa.are_equivalent(&sets, 0, 4));
-        assert!(!dfa.are_equivalent(&sets, 3, 5));
-        assert!(!dfa.are_equivalent(&sets, 0, 3));
-        assert!(!dfa.are_equivalent(&sets, 0, 5));
-        assert!(!dfa.are_equivalent(&sets, 1, 3));
-        assert!(!dfa.are_equivalent(&sets, 1, 5));
-        assert!(!dfa.are_equivalent(&sets, 2, 3));
-        assert!(!dfa.are_equivalent(&sets, 4, 5));
+        assert!(dfa.are_equivalent(&dfa.alphabet, &sets, 1, 2));
+        assert!(dfa.are_equivalent(&dfa.alphabet, &sets, 2, 4));
+        assert!(dfa.are_equivalent(&dfa.alphabet, &sets, 1, 4));
+
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 0, 1));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 0, 2));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 0, 4));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 3, 5));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 0, 3));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 0, 5));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 1, 3));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 1, 5));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 2, 3));
+        assert!(!dfa.are_equivalent(&dfa.alphabet, &sets, 4, 5));
     }
 
     #[test]
@@ -358,4 +635,160 @@ mod tests {
         assert_eq!(3, *dfa.transition_fn.get(&(4, 'a')).unwrap());
         assert_eq!(2, *dfa.transition_fn.get(&(4, 'b')).unwrap());
     }
+
+    #[test]
+    fn dfa_minimization_keeps_start_state() {
+        let dfa = Dfa::from("ab|b").unwrap();
+
+        assert!(!dfa.run("").unwrap());
+        assert!(dfa.run("ab").unwrap());
+        assert!(dfa.run("b").unwrap());
+        assert!(!dfa.run("a").unwrap());
+        assert!(!dfa.run("abb").unwrap());
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    enum Token {
+        Open,
+        Close,
+    }
+
+    #[test]
+    fn dfa_custom_symbols() {
+        // accepts any number of open tokens, each followed by a close token
+        let mut nfa: Nfa<Token> = Nfa::new();
+        nfa.add_state();
+        nfa.add_symbol(Token::Open);
+        nfa.add_symbol(Token::Close);
+        nfa.add_accept_state(0);
+        nfa.add_transition(&(0, Some(Token::Open)), 1).unwrap();
+        nfa.add_transition(&(1, Some(Token::Close)), 0).unwrap();
+
+        let mut dfa = nfa.to_dfa();
+        dfa.minimize().unwrap();
+
+        assert!(dfa.is_complete());
+        assert!(dfa.run_symbols([]).unwrap());
+        assert!(dfa.run_symbols([Token::Open, Token::Close]).unwrap());
+        assert!(dfa
+            .run_symbols([Token::Open, Token::Close, Token::Open, Token::Close])
+            .unwrap());
+        assert!(!dfa.run_symbols([Token::Open, Token::Open]).unwrap());
+        assert!(!dfa.run_symbols([Token::Close]).unwrap());
+    }
+
+    #[test]
+    fn dfa_display() {
+        let mut dfa: Dfa = Dfa::new();
+        dfa.add_state();
+        dfa.add_symbol('b');
+        dfa.add_symbol('a');
+        dfa.add_accept_state(1);
+        dfa.add_transition(&(0, 'a'), 1).unwrap();
+        dfa.add_transition(&(1, 'b'), 0).unwrap();
+        dfa.add_transition(&(1, 'a'), 1).unwrap();
+
+        assert_eq!(
+            "     | a | b\n\
+             →  0 | 1 | ∅\n\
+             \x20* 1 | 1 | 0\n",
+            dfa.to_string()
+        );
+    }
+
+    #[test]
+    fn dfa_is_equivalent() {
+        let dfa = Dfa::from("a|(ab|b)*").unwrap();
+
+        assert!(dfa.is_equivalent(&Nfa::from("a|(ab|b)*").unwrap().to_dfa()));
+        assert!(dfa.is_equivalent(&Nfa::glushkov("(b|ab)*|a").unwrap().to_dfa()));
+        assert!(!dfa.is_equivalent(&Dfa::from("(ab|b)*").unwrap()));
+
+        // an incomplete dfa, over a smaller alphabet
+        let mut incomplete: Dfa = Dfa::new();
+        incomplete.add_state();
+        incomplete.add_symbol('a');
+        incomplete.add_accept_state(1);
+        incomplete.add_transition(&(0, 'a'), 1).unwrap();
+        assert!(incomplete.is_equivalent(&Dfa::from("a").unwrap()));
+        assert!(!incomplete.is_equivalent(&Dfa::from("a|b").unwrap()));
+    }
+
+    #[test]
+    fn dfa_from_table() {
+        // symbols that look like the separators or the ε column
+        let expressions = [
+            "a|(ab|b)*",
+            "(ab)*",
+            "a*b*",
+            "(a|b)*abb",
+            "-",
+            "+",
+            "+-",
+            "(+|-)*ε",
+            "a\\|b c",
+        ];
+        for expression in expressions {
+            let dfa = Dfa::from(expression).unwrap();
+            let read = Dfa::from_table(&dfa.to_string()).unwrap();
+
+            assert_eq!(dfa.states, read.states);
+            assert_eq!(dfa.accept_states, read.accept_states);
+            assert_eq!(dfa.transition_fn, read.transition_fn);
+        }
+
+        // the header of an empty alphabet is an empty line
+        let dfa: Dfa = Dfa::new();
+        assert_eq!("\n→  0\n", dfa.to_string());
+        assert_eq!(1, Dfa::from_table(&dfa.to_string()).unwrap().num_states());
+
+        assert!(Dfa::from_table("  | a\n→ 0 | {0, 1}\n1 | -").is_err());
+        assert!(Dfa::from_table("  | ε\n→ 0 | 0").is_err());
+    }
+
+    #[test]
+    fn dfa_minimization_of_incomplete_table() {
+        // q1 and q2 only differ in where they have no transition
+        let mut dfa = Dfa::from_table(
+            "      | a  | b
+             ->q0  | q1 | q2
+             *q1   | q1 | ∅
+             *q2   | q2 | ∅
+             q3    | ∅  | ∅",
+        )
+        .unwrap();
+        let table = dfa.clone();
+        dfa.minimize().unwrap();
+
+        assert_eq!(3, dfa.num_states());
+        assert!(dfa.is_equivalent(&table));
+        assert!(dfa.run("baa").unwrap());
+        assert!(!dfa.run("").unwrap());
+    }
+
+    #[test]
+    fn dfa_to_dot() {
+        let mut dfa: Dfa = Dfa::new();
+        dfa.add_state();
+        dfa.add_symbol('a');
+        dfa.add_symbol('b');
+        dfa.add_accept_state(1);
+        dfa.add_transition(&(0, 'a'), 1).unwrap();
+        dfa.add_transition(&(0, 'b'), 1).unwrap();
+        dfa.add_transition(&(1, 'a'), 0).unwrap();
+
+        assert_eq!(
+            "digraph {\n    \
+             rankdir=LR;\n    \
+             node [shape=circle];\n    \
+             __start [shape=point];\n    \
+             __start -> 0;\n    \
+             0;\n    \
+             1 [shape=doublecircle];\n    \
+             0 -> 1 [label=\"a, b\"];\n    \
+             1 -> 0 [label=\"a\"];\n\
+             }\n",
+            dfa.to_dot()
+        );
+    }
 }
@@ -0,0 +1,120 @@
+use super::Dfa;
+use crate::automata::att::{self, Att};
+use crate::automata::traits::*;
+
+impl Dfa {
+    /// Reads a dfa from an acceptor in the AT&T text format of OpenFst.
+    /// Like in OpenFst, the labels are symbol names if a symbol table is
+    /// given, and numbers otherwise, which are read as code points.
+    /// The acceptor can't have `<eps>` labels or more than one
+    /// transition from a state on the same symbol.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from("(a|b)*abb").unwrap();
+    /// let table = dfa.att_symbol_table();
+    ///
+    /// let read = Dfa::from_att("0 1 a\n1 2 b\n2\n", Some(&table)).unwrap();
+    /// assert!(read.run("ab").unwrap());
+    ///
+    /// let read = Dfa::from_att("0 1 97\n1 2 98\n2\n", None).unwrap();
+    /// assert!(read.run("ab").unwrap());
+    /// ```
+    pub fn from_att(input: &str, symbol_table: Option<&str>) -> Result<Self, &'static str> {
+        let att = Att::parse(input, symbol_table)?;
+
+        let mut dfa = Dfa::new();
+        (1..att.num_states).for_each(|_| dfa.add_state());
+        att.alphabet()
+            .into_iter()
+            .for_each(|symbol| dfa.add_symbol(symbol));
+        att.accept_states
+            .iter()
+            .for_each(|&state| dfa.add_accept_state(state));
+
+        for &(source, symbol, target) in att.transitions.iter() {
+            let symbol = symbol.ok_or("A dfa can't have transitions on the empty string")?;
+            dfa.add_transition(&(source, symbol), target)?;
+        }
+
+        Ok(dfa)
+    }
+
+    /// Returns the dfa in the AT&T text format,
+    /// with the code points of the symbols as labels
+    pub fn to_att(&self) -> String {
+        self.att().to_text(None).unwrap()
+    }
+
+    /// Returns the dfa in the AT&T text format, with symbol names
+    /// from the given symbol table as labels, which OpenFst reads
+    /// with `fstcompile --acceptor --isymbols`
+    pub fn to_att_with(&self, symbol_table: &str) -> Result<String, &'static str> {
+        self.att().to_text(Some(symbol_table))
+    }
+
+    fn att(&self) -> Att {
+        let first = self.start_state();
+
+        Att {
+            num_states: self.num_states() as u32,
+            accept_states: self
+                .accept_states
+                .iter()
+                .map(|state| state - first)
+                .collect(),
+            transitions: self
+                .transition_fn
+                .iter()
+                .map(|((source, symbol), target)| (source - first, Some(*symbol), target - first))
+                .collect(),
+        }
+    }
+
+    /// Returns the symbol table of the dfa, with `<eps>` as 0
+    /// and the symbols of the alphabet numbered from 1
+    pub fn att_symbol_table(&self) -> String {
+        att::symbol_table(&self.alphabet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dfa_att_round_trip() {
+        let dfa = Dfa::from("(a|b)*abb").unwrap();
+        let read = Dfa::from_att(&dfa.to_att(), None).unwrap();
+
+        assert_eq!(dfa.states, read.states);
+        assert_eq!(dfa.accept_states, read.accept_states);
+        assert_eq!(dfa.transition_fn, read.transition_fn);
+
+        let table = dfa.att_symbol_table();
+        let text = dfa.to_att_with(&table).unwrap();
+        assert!(text.contains("\ta\n"));
+        let read = Dfa::from_att(&text, Some(&table)).unwrap();
+        assert_eq!(dfa.transition_fn, read.transition_fn);
+        assert!(dfa.to_att_with("<eps> 0\na 1\n").is_err());
+    }
+
+    #[test]
+    fn dfa_from_att_rejects_nfa() {
+        assert!(Dfa::from_att("0 1 0\n1\n", None).is_err());
+        assert!(Dfa::from_att("0 1 97\n0 0 97\n1\n", None).is_err());
+    }
+
+    #[test]
+    fn dfa_from_att_minimizes_incomplete() {
+        // states 1 and 2 have no transition on b
+        let mut dfa = Dfa::from_att("0 1 97\n0 2 98\n1 1 97\n2 2 97\n1\n2\n", None).unwrap();
+        dfa.minimize().unwrap();
+
+        assert_eq!(2, dfa.num_states());
+        assert!(dfa.run("baa").unwrap());
+        assert!(!dfa.run("").unwrap());
+    }
+}
@@ -0,0 +1,410 @@
+use super::search::{self, Match, Search};
+use super::Dfa;
+use std::collections::HashMap;
+
+pub mod binary;
+
+/// Number of chars that have their class stored in a lookup table
+const ASCII_LEN: usize = 128;
+
+/// Number of words in the header, before the ASCII classes
+const HEADER_LEN: usize = 6;
+
+/// Storage of the words of a dense dfa, in the order of
+/// the binary format described in `binary`, without the
+/// magic at the start and the checksum at the end
+pub trait Words {
+    fn word(&self, index: usize) -> u32;
+
+    fn num_words(&self) -> usize;
+}
+
+impl Words for Vec<u32> {
+    #[inline]
+    fn word(&self, index: usize) -> u32 {
+        self[index]
+    }
+
+    fn num_words(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Little-endian words that don't have to be aligned
+impl Words for &[u8] {
+    #[inline]
+    fn word(&self, index: usize) -> u32 {
+        u32::from_le_bytes(self[4 * index..4 * index + 4].try_into().unwrap())
+    }
+
+    fn num_words(&self) -> usize {
+        self.len() / 4
+    }
+}
+
+/// Indexes of the parts of the words
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Layout {
+    wide_chars: usize,
+    accept_states: usize,
+    transition_table: usize,
+    len: usize,
+}
+
+impl Layout {
+    fn new(num_states: usize, num_classes: usize, num_wide: usize) -> Option<Self> {
+        let wide_chars = HEADER_LEN + ASCII_LEN;
+        let accept_states = wide_chars.checked_add(num_wide.checked_mul(2)?)?;
+        let transition_table = accept_states.checked_add(num_states.div_ceil(32))?;
+        let len = transition_table.checked_add(num_states.checked_mul(num_classes)?)?;
+
+        Some(Layout {
+            wide_chars,
+            accept_states,
+            transition_table,
+            len,
+        })
+    }
+}
+
+/// A dfa compiled in to a flat transition table, stored in
+/// any kind of `Words`. It's used through `DenseDfa`, which
+/// owns it's table, and `DenseDfaRef`, which reads it straight
+/// from bytes in the binary format.
+///
+/// Symbols that behave the same in every state are put in to the
+/// same class, so the table only needs a column for every class.
+/// Class 0 holds every char that isn't in the alphabet. States that
+/// can't reach an accept state are merged in to a single dead state,
+/// which lets `run` stop early.
+///
+/// # Example
+/// ```rust
+/// use fmsi::Dfa;
+///
+/// let dfa = Dfa::from("(a|b|c)*x").unwrap();
+/// let dense = dfa.compile();
+///
+/// // 'a', 'b' and 'c' share a class
+/// assert_eq!(3, dense.num_classes());
+/// assert!(dense.run("abcax"));
+/// assert!(!dense.run("abcaxa"));
+/// assert!(!dense.run("dx"));
+/// assert_eq!(3..5, dense.find("yyyaxa").unwrap().range());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dense<W> {
+    words: W,
+    layout: Layout,
+    num_states: usize,
+    num_classes: usize,
+    num_wide: usize,
+    start_state: u32,
+    dead_state: u32,
+}
+
+/// A dense dfa that owns it's transition table
+pub type DenseDfa = Dense<Vec<u32>>;
+
+/// A dense dfa that matches directly on bytes in the binary
+/// format, without copying them. The bytes are validated once,
+/// when loaded with `DenseDfaRef::from_bytes`.
+pub type DenseDfaRef<'a> = Dense<&'a [u8]>;
+
+impl<W: Words> Dense<W> {
+    /// Reads the header and checks that there are enough words
+    fn from_words(words: W) -> Result<Self, &'static str> {
+        if words.num_words() < HEADER_LEN {
+            return Err("The compiled dfa has the wrong length");
+        }
+
+        let num_states = words.word(1) as usize;
+        let num_classes = words.word(2) as usize;
+        let num_wide = words.word(5) as usize;
+        let layout = Layout::new(num_states, num_classes, num_wide)
+            .ok_or("The compiled dfa is too large")?;
+        if words.num_words() != layout.len {
+            return Err("The compiled dfa has the wrong length");
+        }
+
+        Ok(Dense {
+            start_state: words.word(3),
+            dead_state: words.word(4),
+            words,
+            layout,
+            num_states,
+            num_classes,
+            num_wide,
+        })
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.num_states
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.num_classes
+    }
+
+    #[inline]
+    pub fn start_state(&self) -> u32 {
+        self.start_state
+    }
+
+    /// Returns the state that can't reach an accept state
+    #[inline]
+    pub fn dead_state(&self) -> u32 {
+        self.dead_state
+    }
+
+    #[inline]
+    pub fn is_accept_state(&self, state: u32) -> bool {
+        let word = self
+            .words
+            .word(self.layout.accept_states + state as usize / 32);
+        word & (1 << (state % 32)) != 0
+    }
+
+    /// Returns the class of the given char
+    #[inline]
+    pub fn class(&self, symbol: char) -> u32 {
+        if (symbol as usize) < ASCII_LEN {
+            return self.words.word(HEADER_LEN + symbol as usize);
+        }
+
+        // binary search through the sorted wide chars
+        let (mut low, mut high) = (0, self.num_wide);
+        while low < high {
+            let middle = (low + high) / 2;
+            let index = self.layout.wide_chars + 2 * middle;
+
+            match self.words.word(index).cmp(&(symbol as u32)) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return self.words.word(index + 1),
+            }
+        }
+
+        0
+    }
+
+    #[inline]
+    pub fn next_state(&self, state: u32, symbol: char) -> u32 {
+        let index = state as usize * self.num_classes + self.class(symbol) as usize;
+        self.words.word(self.layout.transition_table + index)
+    }
+
+    /// Processes the given string and returns true
+    /// if it ends up in an accept state.
+    pub fn run(&self, input: &str) -> bool {
+        let mut state = self.start_state;
+
+        for symbol in input.chars() {
+            if state == self.dead_state {
+                return false;
+            }
+            state = self.next_state(state, symbol);
+        }
+
+        self.is_accept_state(state)
+    }
+
+    /// Returns true if any substring of the given string
+    /// is accepted, like `Dfa::is_match`
+    pub fn is_match(&self, text: &str) -> bool {
+        search::is_match(self, text)
+    }
+
+    /// Returns the leftmost-longest match inside
+    /// of the given string, like `Dfa::find`
+    pub fn find<'t>(&self, text: &'t str) -> Option<Match<'t>> {
+        search::leftmost_longest(self, text, 0).map(|(start, end)| Match::new(text, start, end))
+    }
+}
+
+impl<W: Words> Search for Dense<W> {
+    fn state_bound(&self) -> usize {
+        self.num_states
+    }
+
+    fn start(&self) -> u32 {
+        self.start_state
+    }
+
+    fn accepts(&self, state: u32) -> bool {
+        self.is_accept_state(state)
+    }
+
+    fn is_dead(&self, state: u32) -> bool {
+        state == self.dead_state
+    }
+
+    fn next(&self, state: u32, symbol: char) -> Option<u32> {
+        Some(self.next_state(state, symbol))
+    }
+}
+
+impl DenseDfa {
+    pub fn new(dfa: &Dfa) -> Self {
+        let live = dfa.live_states();
+
+        // live states keep their order, everything else becomes the dead state
+        let live_rows: Vec<u32> = dfa
+            .states
+            .iter()
+            .copied()
+            .filter(|&state| live[state as usize])
+            .collect();
+        let rows: HashMap<u32, u32> = live_rows
+            .iter()
+            .enumerate()
+            .map(|(row, &state)| (state, row as u32))
+            .collect();
+        let dead_state = live_rows.len() as u32;
+        let row = |state: Option<&u32>| {
+            state
+                .and_then(|state| rows.get(state))
+                .copied()
+                .unwrap_or(dead_state)
+        };
+
+        // symbols whose columns are the same share a class,
+        // a column that only leads to the dead state belongs to class 0
+        let mut alphabet = dfa.alphabet.clone();
+        alphabet.sort();
+
+        let mut columns: HashMap<Vec<u32>, u32> = HashMap::new();
+        columns.insert(vec![dead_state; live_rows.len()], 0);
+
+        let mut ascii_classes = [0; ASCII_LEN];
+        let mut wide_chars: Vec<u32> = Vec::new();
+        let mut representatives: Vec<Option<char>> = vec![None];
+
+        for &symbol in alphabet.iter() {
+            let column: Vec<u32> = live_rows
+                .iter()
+                .map(|&state| row(dfa.transition_fn.get(&(state, symbol))))
+                .collect();
+
+            let class = match columns.get(&column) {
+                Some(&class) => class,
+                None => {
+                    let class = representatives.len() as u32;
+                    columns.insert(column, class);
+                    representatives.push(Some(symbol));
+                    class
+                }
+            };
+
+            if (symbol as usize) < ASCII_LEN {
+                ascii_classes[symbol as usize] = class;
+            } else {
+                wide_chars.extend([symbol as u32, class]);
+            }
+        }
+
+        let num_states = live_rows.len() + 1;
+        let num_classes = representatives.len();
+
+        let mut words = vec![
+            binary::VERSION,
+            num_states as u32,
+            num_classes as u32,
+            row(Some(&dfa.start_state())),
+            dead_state,
+            wide_chars.len() as u32 / 2,
+        ];
+        words.extend(ascii_classes);
+        words.extend(wide_chars);
+
+        let mut accept_states = vec![0u32; num_states.div_ceil(32)];
+        for (row, &state) in live_rows.iter().enumerate() {
+            if dfa.is_accept_state(state) {
+                accept_states[row / 32] |= 1 << (row % 32);
+            }
+        }
+        words.extend(accept_states);
+
+        for &state in live_rows.iter() {
+            for representative in representatives.iter() {
+                words.push(match representative {
+                    Some(symbol) => row(dfa.transition_fn.get(&(state, *symbol))),
+                    None => dead_state,
+                });
+            }
+        }
+        words.extend(std::iter::repeat_n(dead_state, num_classes));
+
+        Dense::from_words(words).unwrap()
+    }
+}
+
+impl Dfa {
+    /// Compiles the dfa in to a dense transition table
+    pub fn compile(&self) -> DenseDfa {
+        DenseDfa::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::traits::*;
+
+    #[test]
+    fn dense_dfa_agrees_with_dfa() {
+        let inputs = [
+            "", "a", "ab", "abb", "ba", "abab", "bbbb", "abc", "čab", "aaa",
+        ];
+
+        for expression in ["a|(ab|b)*", "ab|b", "(ab)*", "a*b*", "(a|b)*abb", "č(a|b)*"] {
+            let dfa = Dfa::from(expression).unwrap();
+            let dense = dfa.compile();
+
+            for input in inputs {
+                assert_eq!(
+                    dfa.run(input).unwrap_or(false),
+                    dense.run(input),
+                    "{} {:?}",
+                    expression,
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dense_dfa_classes() {
+        let dense = Dfa::from("(a|b|c|ž)*x").unwrap().compile();
+
+        assert_eq!(3, dense.num_classes());
+        assert_eq!(dense.class('a'), dense.class('ž'));
+        assert_ne!(dense.class('a'), dense.class('x'));
+        assert_eq!(0, dense.class('y'));
+        assert_eq!(0, dense.class('č'));
+    }
+
+    #[test]
+    fn dense_dfa_dead_state() {
+        let dense = Dfa::from("ab").unwrap().compile();
+
+        // the trap state of the complete dfa becomes the dead state
+        let state = dense.next_state(dense.start_state(), 'b');
+        assert_eq!(dense.dead_state(), state);
+        assert!(!dense.is_accept_state(state));
+
+        // an incomplete dfa
+        let mut dfa = Dfa::new();
+        dfa.add_state();
+        dfa.add_symbol('a');
+        dfa.add_symbol('b');
+        dfa.add_accept_state(1);
+        dfa.add_transition(&(0, 'a'), 1).unwrap();
+
+        let dense = dfa.compile();
+        assert_eq!(3, dense.num_states());
+        assert!(dense.run("a"));
+        assert!(!dense.run("b"));
+        assert!(!dense.run("aa"));
+    }
+}
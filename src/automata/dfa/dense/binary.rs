@@ -0,0 +1,271 @@
+//! A binary format for compiled dfas, meant for embedding them
+//! with `include_bytes!` and matching without deserializing them.
+//!
+//! Every number is a little-endian `u32`, so the bytes need no alignment
+//! and read the same on every platform. The layout is:
+//!
+//! | field                | size                           |
+//! |----------------------|--------------------------------|
+//! | magic `b"FMSIDFA\0"` | 8 bytes                        |
+//! | version              | 1                              |
+//! | number of states     | 1                              |
+//! | number of classes    | 1                              |
+//! | start state          | 1                              |
+//! | dead state           | 1                              |
+//! | number of wide chars | 1                              |
+//! | ASCII classes        | 128                            |
+//! | wide chars           | 2 per char (char, class)       |
+//! | accept states        | bitset, 1 per 32 states        |
+//! | transition table     | states * classes               |
+//! | CRC-32 checksum      | 1, of all the preceding bytes  |
+
+use super::{Dense, DenseDfa, DenseDfaRef, Words, ASCII_LEN, HEADER_LEN};
+use crate::automata::dfa::Dfa;
+
+const MAGIC: &[u8; 8] = b"FMSIDFA\0";
+
+/// Version of the format, bumped on every incompatible change
+pub const VERSION: u32 = 1;
+
+/// Lookup table of the CRC-32 used by zlib and png
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc: u32, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+impl DenseDfa {
+    /// Encodes the dfa in the binary format, which can be
+    /// matched on directly with `DenseDfaRef::from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 4 * self.words.len() + 4);
+        bytes.extend_from_slice(MAGIC);
+        for word in self.words.iter() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        let checksum = crc32(&bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+
+        bytes
+    }
+
+    /// Decodes a dfa encoded with `DenseDfa::to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        Ok(DenseDfaRef::from_bytes(bytes)?.to_dense())
+    }
+}
+
+impl Dfa {
+    /// Compiles the dfa and encodes it in the binary format
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::dfa::DenseDfaRef;
+    /// use fmsi::Dfa;
+    ///
+    /// let bytes = Dfa::from("(a|b)*abb").unwrap().to_bytes();
+    /// let dfa = DenseDfaRef::from_bytes(&bytes).unwrap();
+    ///
+    /// assert!(dfa.run("babb"));
+    /// assert!(!dfa.run("abba"));
+    /// assert_eq!("aabb", dfa.find("xaabbx").unwrap().as_str());
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.compile().to_bytes()
+    }
+}
+
+impl<'a> DenseDfaRef<'a> {
+    /// Checks the magic, version, checksum and contents of the bytes
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, &'static str> {
+        let header_len = MAGIC.len() + 4 * HEADER_LEN;
+        if bytes.len() < header_len + 4 || &bytes[..MAGIC.len()] != MAGIC {
+            return Err("The bytes are not a compiled dfa");
+        }
+
+        let checksum = bytes.len() - 4;
+        let words = &bytes[MAGIC.len()..checksum];
+        if words.word(0) != VERSION {
+            return Err("The compiled dfa has an unsupported version");
+        }
+        if words.len() % 4 != 0 {
+            return Err("The compiled dfa has the wrong length");
+        }
+
+        let dfa = Dense::from_words(words)?;
+        if (&bytes[checksum..]).word(0) != crc32(&bytes[..checksum]) {
+            return Err("The checksum of the compiled dfa doesn't match");
+        }
+
+        dfa.validate()?;
+        Ok(dfa)
+    }
+
+    /// Checks that every state and class is in range
+    /// and that the wide chars are sorted
+    fn validate(&self) -> Result<(), &'static str> {
+        let layout = &self.layout;
+        let num_states = self.num_states as u32;
+        let num_classes = self.num_classes as u32;
+
+        if self.start_state >= num_states || self.dead_state >= num_states {
+            return Err("The compiled dfa has an invalid start or dead state");
+        }
+
+        let mut previous = None;
+        for index in 0..self.num_wide {
+            let symbol = self.words.word(layout.wide_chars + 2 * index);
+            let class = self.words.word(layout.wide_chars + 2 * index + 1);
+
+            if char::from_u32(symbol).is_none() || previous.is_some_and(|p| p >= symbol) {
+                return Err("The compiled dfa has invalid chars");
+            }
+            if class >= num_classes {
+                return Err("The compiled dfa has an invalid class");
+            }
+            previous = Some(symbol);
+        }
+        if (HEADER_LEN..HEADER_LEN + ASCII_LEN).any(|index| self.words.word(index) >= num_classes) {
+            return Err("The compiled dfa has an invalid class");
+        }
+
+        if (layout.transition_table..layout.len).any(|index| self.words.word(index) >= num_states) {
+            return Err("The compiled dfa has an invalid transition");
+        }
+
+        Ok(())
+    }
+
+    /// Copies the dfa out of the bytes
+    pub fn to_dense(&self) -> DenseDfa {
+        Dense {
+            words: (0..self.layout.len)
+                .map(|index| self.words.word(index))
+                .collect(),
+            layout: self.layout,
+            num_states: self.num_states,
+            num_classes: self.num_classes,
+            num_wide: self.num_wide,
+            start_state: self.start_state,
+            dead_state: self.dead_state,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPRESSIONS: [&str; 7] = [
+        "a|(ab|b)*",
+        "ab|b",
+        "(ab)*",
+        "a*b*",
+        "(a|b)*abb",
+        "č(a|b)*",
+        "(if|else|while|for|return|ž)",
+    ];
+
+    const INPUTS: [&str; 12] = [
+        "", "a", "ab", "abb", "ba", "abab", "bbbb", "abc", "čab", "while", "ž", "elsa",
+    ];
+
+    #[test]
+    fn binary_round_trip() {
+        for expression in EXPRESSIONS {
+            let dfa = Dfa::from(expression).unwrap();
+            let dense = dfa.compile();
+            let bytes = dense.to_bytes();
+
+            assert_eq!(dense, DenseDfa::from_bytes(&bytes).unwrap());
+
+            let loaded = DenseDfaRef::from_bytes(&bytes).unwrap();
+            assert_eq!(dense.num_states(), loaded.num_states());
+            assert_eq!(dense.num_classes(), loaded.num_classes());
+            for input in INPUTS {
+                assert_eq!(
+                    dense.run(input),
+                    loaded.run(input),
+                    "{} {:?}",
+                    expression,
+                    input
+                );
+                assert_eq!(dfa.run(input).unwrap_or(false), loaded.run(input));
+                assert_eq!(dfa.is_match(input), loaded.is_match(input));
+                assert_eq!(
+                    dfa.find(input).map(|found| found.range()),
+                    loaded.find(input).map(|found| found.range())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn binary_layout() {
+        let bytes = Dfa::from("ab").unwrap().to_bytes();
+
+        assert_eq!(b"FMSIDFA\0", &bytes[..8]);
+        assert_eq!([1, 0, 0, 0], bytes[8..12]);
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+
+        // the bytes don't have to be aligned
+        let mut shifted = vec![0];
+        shifted.extend_from_slice(&bytes);
+        assert!(DenseDfaRef::from_bytes(&shifted[1..]).unwrap().run("ab"));
+    }
+
+    #[test]
+    fn binary_validation() {
+        let bytes = Dfa::from("(a|b)*abb").unwrap().to_bytes();
+
+        assert!(DenseDfaRef::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(DenseDfaRef::from_bytes(&bytes[..20]).is_err());
+        assert!(DenseDfaRef::from_bytes(b"").is_err());
+
+        let mut corrupted = bytes.clone();
+        corrupted[8] = 2;
+        assert_eq!(
+            Err("The compiled dfa has an unsupported version"),
+            DenseDfaRef::from_bytes(&corrupted)
+        );
+
+        let mut corrupted = bytes.clone();
+        let last = corrupted.len() - 5;
+        corrupted[last] ^= 1;
+        assert_eq!(
+            Err("The checksum of the compiled dfa doesn't match"),
+            DenseDfaRef::from_bytes(&corrupted)
+        );
+
+        // a transition out of range, with a matching checksum
+        let mut corrupted = bytes.clone();
+        let checksum = corrupted.len() - 4;
+        corrupted[checksum - 4..checksum].copy_from_slice(&1000u32.to_le_bytes());
+        let crc = crc32(&corrupted[..checksum]);
+        corrupted[checksum..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(
+            Err("The compiled dfa has an invalid transition"),
+            DenseDfaRef::from_bytes(&corrupted)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use super::Dfa;
+use crate::language::Regex;
+use std::collections::{HashMap, VecDeque};
+
+impl Dfa {
+    /// Builds a complete dfa whose states are the Brzozowski derivatives
+    /// of the given regex, and returns it along with the regex of every
+    /// state. Every state accepts the strings matched by it's regex.
+    ///
+    /// Derivatives are compared after flattening, sorting and
+    /// deduplicating unions, which makes their number finite.
+    /// The alphabet consists of the chars used in the regex.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::language::Regex;
+    /// use fmsi::Dfa;
+    ///
+    /// let regex = Regex::parse("ab*").unwrap();
+    /// let (dfa, labels) = Dfa::from_derivatives(&regex).unwrap();
+    ///
+    /// let labels: Vec<String> = labels.iter().map(|label| label.to_string()).collect();
+    /// assert_eq!(vec!["ab*", "b*", "[^\\u{0}-\\u{10ffff}]"], labels);
+    /// assert!(dfa.run("abb").unwrap());
+    /// ```
+    pub fn from_derivatives(regex: &Regex) -> Result<(Self, Vec<Regex>), &'static str> {
+        let alphabet = regex.alphabet()?;
+        let mut states: Vec<Regex> = vec![regex.normalized()];
+        let mut lookup_table: HashMap<Regex, u32> = HashMap::from([(states[0].clone(), 0)]);
+        let mut queue: VecDeque<u32> = VecDeque::from([0]);
+        let mut transition_fn: HashMap<(u32, char), u32> = HashMap::new();
+
+        while let Some(current) = queue.pop_front() {
+            for &symbol in alphabet.iter() {
+                let derivative = states[current as usize].derivative(symbol);
+
+                let target = match lookup_table.get(&derivative) {
+                    Some(&target) => target,
+                    None => {
+                        let target = states.len() as u32;
+                        lookup_table.insert(derivative.clone(), target);
+                        states.push(derivative);
+                        queue.push_back(target);
+                        target
+                    }
+                };
+
+                transition_fn.insert((current, symbol), target);
+            }
+        }
+
+        let accept_states = (0..states.len() as u32)
+            .filter(|&state| states[state as usize].nullable())
+            .collect();
+
+        let dfa = Dfa::from_parts(
+            (0..states.len() as u32).collect(),
+            alphabet,
+            transition_fn,
+            accept_states,
+        );
+
+        Ok((dfa, states))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dfa_from_derivatives() {
+        let regex = Regex::parse("a|(ab|b)*").unwrap();
+        let (dfa, labels) = Dfa::from_derivatives(&regex).unwrap();
+
+        assert!(dfa.is_complete());
+        assert_eq!(dfa.num_states(), labels.len());
+        for (state, label) in labels.iter().enumerate() {
+            assert_eq!(label.nullable(), dfa.is_accept_state(state as u32));
+        }
+
+        let mut minimized = dfa.clone();
+        minimized.minimize().unwrap();
+        assert_eq!(5, minimized.num_states());
+    }
+
+    #[test]
+    fn dfa_from_derivatives_agrees_with_subset_construction() {
+        let inputs = [
+            "", "a", "b", "ab", "ba", "abb", "abab", "aab", "bbbab", "c", "abc",
+        ];
+
+        for expression in ["a|(ab|b)*", "(ab)*", "a*b*", "(a|b)*abb", "((a|())b)*c"] {
+            let regex = Regex::parse(expression).unwrap();
+            let (derivatives, _) = Dfa::from_derivatives(&regex).unwrap();
+            let subsets = Dfa::from(expression).unwrap();
+
+            for input in inputs {
+                assert_eq!(
+                    subsets.run(input).unwrap_or(false),
+                    derivatives.run(input).unwrap_or(false),
+                    "{} {:?}",
+                    expression,
+                    input
+                );
+                assert_eq!(
+                    regex.matches(input),
+                    derivatives.run(input).unwrap_or(false)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use super::Dfa;
+use crate::automata::jflap::{Coordinates, Jflap};
+use crate::automata::traits::*;
+
+impl Dfa {
+    /// Reads a dfa from a JFLAP file, and returns it along with
+    /// the positions of it's states. The automata in the file
+    /// can't have lambda transitions or more than one transition
+    /// from a state on the same symbol.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from("a|(ab|b)*").unwrap();
+    /// let (read, _) = Dfa::from_jflap(&dfa.to_jflap()).unwrap();
+    ///
+    /// assert_eq!(dfa.num_states(), read.num_states());
+    /// assert!(read.run("abbab").unwrap());
+    /// ```
+    pub fn from_jflap(input: &str) -> Result<(Self, Coordinates), &'static str> {
+        let jflap = Jflap::parse(input)?;
+
+        let mut dfa = Dfa::new();
+        (1..jflap.num_states).for_each(|_| dfa.add_state());
+        jflap
+            .alphabet()
+            .into_iter()
+            .for_each(|symbol| dfa.add_symbol(symbol));
+        jflap
+            .accept_states
+            .iter()
+            .for_each(|&state| dfa.add_accept_state(state));
+
+        for &(source, symbol, target) in jflap.transitions.iter() {
+            let symbol = symbol.ok_or("A dfa can't have transitions on the empty string")?;
+            dfa.add_transition(&(source, symbol), target)?;
+        }
+
+        Ok((dfa, jflap.coordinates))
+    }
+
+    /// Returns the dfa as a JFLAP file, with the states placed on a line
+    pub fn to_jflap(&self) -> String {
+        self.to_jflap_with(&Coordinates::new())
+    }
+
+    /// Returns the dfa as a JFLAP file, with the states
+    /// at the given positions
+    pub fn to_jflap_with(&self, coordinates: &Coordinates) -> String {
+        let first = self.start_state();
+
+        let jflap = Jflap {
+            num_states: self.num_states() as u32,
+            accept_states: self
+                .accept_states
+                .iter()
+                .map(|state| state - first)
+                .collect(),
+            transitions: self
+                .transition_fn
+                .iter()
+                .map(|((source, symbol), target)| (source - first, Some(*symbol), target - first))
+                .collect(),
+            coordinates: coordinates
+                .iter()
+                .filter(|(state, _)| self.states.contains(state))
+                .map(|(state, &position)| (state - first, position))
+                .collect(),
+        };
+
+        jflap.to_xml()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dfa_jflap_round_trip() {
+        let dfa = Dfa::from("(a|b)*abb").unwrap();
+        let coordinates = Coordinates::from([(1, (3.0, 4.0))]);
+
+        let (read, read_coordinates) = Dfa::from_jflap(&dfa.to_jflap_with(&coordinates)).unwrap();
+
+        assert_eq!(dfa.states, read.states);
+        assert_eq!(dfa.accept_states, read.accept_states);
+        assert_eq!(dfa.transition_fn, read.transition_fn);
+        assert_eq!(Some(&(3.0, 4.0)), read_coordinates.get(&1));
+    }
+
+    #[test]
+    fn dfa_from_jflap_rejects_nfa() {
+        let nfa = crate::Nfa::from("a*").unwrap();
+        assert!(Dfa::from_jflap(&nfa.to_jflap()).is_err());
+    }
+
+    #[test]
+    fn dfa_from_jflap_minimizes_incomplete() {
+        // 1 and 2 only accept a*, and have no transition on b
+        let dfa = Dfa::from_table("|a|b\n→0|1|2\n*1|1|∅\n*2|2|∅").unwrap();
+        let (mut read, _) = Dfa::from_jflap(&dfa.to_jflap()).unwrap();
+        read.minimize().unwrap();
+
+        assert_eq!(2, read.num_states());
+        assert!(read.is_equivalent(&dfa));
+    }
+}
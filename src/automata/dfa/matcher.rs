@@ -0,0 +1,218 @@
+use super::Dfa;
+use std::str;
+
+/// Keeps track of the current state of a dfa
+/// while the input arrives in chunks.
+///
+/// Chars whose UTF-8 encoding is split between two chunks
+/// given to `feed_bytes` are put back together. Invalid UTF-8
+/// can't be accepted, so it sends the matcher in to a dead state.
+///
+/// # Example
+/// ```rust
+/// use fmsi::Dfa;
+///
+/// let dfa = Dfa::from("a(b|c)*").unwrap();
+/// let mut matcher = dfa.matcher();
+///
+/// matcher.feed("ab");
+/// matcher.feed("cb");
+/// assert!(matcher.is_accepting());
+///
+/// matcher.feed("a");
+/// assert!(matcher.is_dead());
+///
+/// matcher.reset();
+/// assert!(!matcher.is_accepting());
+/// assert!(!matcher.is_dead());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DfaMatcher<'d> {
+    dfa: &'d Dfa,
+    live: &'d [bool],
+    state: Option<u32>,
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+impl<'d> DfaMatcher<'d> {
+    pub fn new(dfa: &'d Dfa) -> Self {
+        DfaMatcher {
+            dfa,
+            live: dfa.live_states(),
+            state: Some(dfa.start_state()),
+            pending: [0; 4],
+            pending_len: 0,
+        }
+    }
+
+    /// Returns the current state, or None if the matcher
+    /// read a symbol it had no transition for
+    #[inline]
+    pub fn state(&self) -> Option<u32> {
+        self.state
+    }
+
+    /// Moves the matcher back to the start state
+    pub fn reset(&mut self) {
+        self.state = Some(self.dfa.start_state());
+        self.pending_len = 0;
+    }
+
+    /// Returns true if everything fed so far is accepted by the dfa
+    pub fn is_accepting(&self) -> bool {
+        // a char that's only partially fed can't be accepted
+        self.pending_len == 0 && self.state.is_some_and(|state| self.dfa.is_accept_state(state))
+    }
+
+    /// Returns true if no accept state can be reached
+    /// regardless of the input that follows
+    pub fn is_dead(&self) -> bool {
+        match self.state {
+            Some(state) => !self.live[state as usize],
+            None => true,
+        }
+    }
+
+    /// Processes the next chunk of the input
+    pub fn feed(&mut self, input: &str) {
+        // the previous chunk ended in the middle of a char
+        if self.pending_len > 0 && !input.is_empty() {
+            self.state = None;
+            self.pending_len = 0;
+        }
+
+        for symbol in input.chars() {
+            if self.is_dead() {
+                return;
+            }
+            self.step(symbol);
+        }
+    }
+
+    /// Processes the next chunk of the input given as UTF-8 bytes
+    pub fn feed_bytes(&mut self, mut input: &[u8]) {
+        // finish the char that was split between the chunks first
+        while self.pending_len > 0 && !input.is_empty() {
+            self.pending[self.pending_len] = input[0];
+            self.pending_len += 1;
+            input = &input[1..];
+
+            match str::from_utf8(&self.pending[..self.pending_len]) {
+                Ok(symbol) => {
+                    let symbol = symbol.chars().next().unwrap();
+                    self.pending_len = 0;
+                    self.step(symbol);
+                }
+                // the char is still incomplete
+                Err(error) if error.error_len().is_none() => {}
+                Err(_) => {
+                    self.state = None;
+                    self.pending_len = 0;
+                }
+            }
+        }
+
+        match str::from_utf8(input) {
+            Ok(input) => self.feed(input),
+            Err(error) => {
+                let (valid, rest) = input.split_at(error.valid_up_to());
+                self.feed(str::from_utf8(valid).unwrap());
+
+                if error.error_len().is_some() {
+                    self.state = None;
+                } else {
+                    // the chunk ends in the middle of a char,
+                    // so keep it's bytes until the next chunk
+                    self.pending[..rest.len()].copy_from_slice(rest);
+                    self.pending_len = rest.len();
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, symbol: char) {
+        self.state = self
+            .state
+            .and_then(|state| self.dfa.transition_fn.get(&(state, symbol)).copied());
+    }
+}
+
+impl Dfa {
+    /// Returns a matcher that processes input in chunks
+    pub fn matcher(&self) -> DfaMatcher<'_> {
+        DfaMatcher::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matcher_chunks() {
+        let dfa = Dfa::from("ab*c").unwrap();
+        let mut matcher = dfa.matcher();
+
+        for chunk in ["a", "", "bb", "b"] {
+            matcher.feed(chunk);
+            assert!(!matcher.is_accepting());
+            assert!(!matcher.is_dead());
+        }
+
+        matcher.feed("c");
+        assert!(matcher.is_accepting());
+
+        matcher.feed("c");
+        assert!(!matcher.is_accepting());
+        assert!(matcher.is_dead());
+    }
+
+    #[test]
+    fn matcher_unknown_symbol() {
+        let dfa = Dfa::from("ab").unwrap();
+        let mut matcher = dfa.matcher();
+
+        matcher.feed("x");
+        assert!(matcher.is_dead());
+        assert_eq!(None, matcher.state());
+
+        matcher.reset();
+        matcher.feed("ab");
+        assert!(matcher.is_accepting());
+    }
+
+    #[test]
+    fn matcher_split_chars() {
+        let dfa = Dfa::from("čć*").unwrap();
+        let input = "čćć".as_bytes();
+        let mut matcher = dfa.matcher();
+
+        // feed the input one byte at a time
+        for (index, byte) in input.iter().enumerate() {
+            matcher.feed_bytes(&[*byte]);
+            assert_eq!(index % 2 == 1, matcher.is_accepting());
+            assert!(!matcher.is_dead());
+        }
+
+        matcher.reset();
+        matcher.feed_bytes(&input[..3]);
+        matcher.feed_bytes(&input[3..]);
+        assert!(matcher.is_accepting());
+    }
+
+    #[test]
+    fn matcher_invalid_utf8() {
+        let dfa = Dfa::from("a*").unwrap();
+        let mut matcher = dfa.matcher();
+
+        matcher.feed_bytes(b"aa\xffa");
+        assert!(matcher.is_dead());
+
+        // a char that never gets finished
+        matcher.reset();
+        matcher.feed_bytes(&"č".as_bytes()[..1]);
+        matcher.feed("a");
+        assert!(matcher.is_dead());
+    }
+}
@@ -1,10 +1,12 @@
 use super::Dfa;
+use crate::language::Regex;
 use std::collections::HashMap;
 use std::fmt;
 
 #[derive(Debug, PartialEq, Clone)]
 enum Token {
     Symbol(char),
+    Class(Regex),
     EmptyString,
     EmptySet,
     Union,
@@ -83,7 +85,7 @@ impl Equation {
                 if let Some(token) = self.0.get(empty_index + 1) {
                     if matches!(
                         token,
-                        Token::Symbol(_) | Token::OpenParent | Token::EmptyString) 
+                        Token::Symbol(_) | Token::Class(_) | Token::OpenParent | Token::EmptyString) 
                     {
                         self.0.remove(empty_index);
                         done = false;
@@ -98,6 +100,7 @@ impl Equation {
         if self.0.len() == 1 {
             match self.0[0] {
                 Token::Symbol(_) => {}
+                Token::Class(_) => {}
                 Token::EmptyString => {}
                 _ => {
                     self.0.remove(0);
@@ -125,21 +128,23 @@ impl Default for Equation {
 
 impl fmt::Display for Equation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "{}", Regex::EmptySet);
+        }
+
         let mut regex = String::with_capacity(self.0.len());
 
         for token in self.0.iter() {
             match token {
-                Token::Symbol(symbol) => regex.push(*symbol),
+                Token::Symbol(symbol) => regex.push_str(&Regex::Symbol(*symbol).to_string()),
+                Token::Class(class) => regex.push_str(&class.to_string()),
                 Token::Union => regex.push('|'),
                 Token::KleeneStar => regex.push('*'),
                 Token::OpenParent => regex.push('('),
                 Token::ClosedParent => regex.push(')'),
-                Token::EmptyString => {
-                    // regex.push_str("\\0");
-                    regex.push('𝜖');
-                    // regex.push_str("[empty string]");
-                }
-                Token::EmptySet => {},
+                // written so that `Regex::parse` reads it back
+                Token::EmptyString => regex.push_str("()"),
+                Token::EmptySet => regex.push_str(&Regex::EmptySet.to_string()),
             }
         }
         write!(f, "{}", regex)
@@ -256,7 +261,8 @@ pub fn get_regex(old_dfa: &Dfa) -> Equation {
 
 // Returns an initial equation for for a transition
 // between two states that doesn't pass through
-// eny aditional states
+// eny aditional states. If there are more than
+// two symbols, they are written as a class.
 fn get_initial_eq(dfa: &Dfa, (i, j): (u32, u32)) -> Equation {
     let mut symbols: Vec<char> = Vec::new();
     let mut eqv = Equation::new();
@@ -277,9 +283,15 @@ fn get_initial_eq(dfa: &Dfa, (i, j): (u32, u32)) -> Equation {
     // the symbols get sorted first and then added to the
     // table
     symbols.sort();
-    for symbol in symbols {
-        eqv.0.push(Token::Symbol(symbol));
+    if symbols.len() > 2 {
+        let ranges: Vec<(char, char)> = symbols.iter().map(|&s| (s, s)).collect();
+        eqv.0.push(Token::Class(Regex::class(&ranges)));
         eqv.0.push(Token::Union);
+    } else {
+        for symbol in symbols {
+            eqv.0.push(Token::Symbol(symbol));
+            eqv.0.push(Token::Union);
+        }
     }
 
     // remove the last union
@@ -444,4 +456,40 @@ mod tests {
         let eq = get_regex(&dfa);
         println!("{}", eq);
     }
+
+    #[test]
+    fn regex_generation_round_trip() {
+        let expressions = [
+            "a",
+            "ab|b",
+            "a|(ab|b)*",
+            "(a|b)*abb",
+            "a*b*",
+            "\\*(\\|)",
+            "()",
+        ];
+        for expression in expressions {
+            let dfa = Dfa::from(expression).unwrap();
+            let regex = dfa.to_regex();
+
+            assert!(!regex.contains('𝜖'), "{}", regex);
+            assert!(Dfa::from(&regex).unwrap().is_equivalent(&dfa), "{}", regex);
+        }
+
+        // a dfa without accept states
+        let regex = Dfa::new().to_regex();
+        assert!(!Dfa::from(&regex).unwrap().run("").unwrap());
+    }
+
+    #[test]
+    fn regex_generation_classes() {
+        let dfa = Dfa::from("(a|b|c|d|x)*y").unwrap();
+        let regex = dfa.to_regex();
+
+        assert!(Dfa::from(&regex).unwrap().is_equivalent(&dfa), "{}", regex);
+        assert_eq!(
+            vec![vec!['a', 'b', 'c', 'd', 'x'], vec!['y']],
+            dfa.symbol_classes()
+        );
+    }
 }
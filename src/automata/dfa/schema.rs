@@ -0,0 +1,156 @@
+use super::Dfa;
+use crate::automata::traits::*;
+use crate::language::Symbol;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// The serialized form of a dfa
+#[derive(Serialize, Deserialize)]
+struct DfaSchema<S> {
+    states: Vec<u32>,
+    alphabet: Vec<S>,
+    transitions: Vec<DfaTransition<S>>,
+    start: u32,
+    accepts: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DfaTransition<S> {
+    from: u32,
+    symbol: S,
+    to: u32,
+}
+
+impl<S: Symbol> From<&Dfa<S>> for DfaSchema<S> {
+    fn from(dfa: &Dfa<S>) -> Self {
+        let mut transitions: Vec<(u32, S, u32)> = dfa
+            .transition_fn
+            .iter()
+            .map(|((from, symbol), to)| (*from, symbol.clone(), *to))
+            .collect();
+        transitions.sort();
+
+        DfaSchema {
+            states: dfa.states.clone(),
+            alphabet: dfa.alphabet.clone(),
+            transitions: transitions
+                .into_iter()
+                .map(|(from, symbol, to)| DfaTransition { from, symbol, to })
+                .collect(),
+            start: dfa.start_state(),
+            accepts: dfa.accept_states.clone(),
+        }
+    }
+}
+
+impl<S: Symbol> TryFrom<DfaSchema<S>> for Dfa<S> {
+    type Error = &'static str;
+
+    fn try_from(schema: DfaSchema<S>) -> Result<Self, Self::Error> {
+        if schema.states.first() != Some(&schema.start) {
+            return Err("The first state must be the start state");
+        }
+        if schema
+            .states
+            .iter()
+            .zip(schema.start..)
+            .any(|(&state, expected)| state != expected)
+        {
+            return Err("The states must be consecutive numbers");
+        }
+
+        let mut dfa = Dfa {
+            states: schema.states,
+            alphabet: Vec::new(),
+            transition_fn: HashMap::new(),
+            accept_states: Vec::new(),
+            live: OnceLock::new(),
+        };
+
+        for symbol in schema.alphabet {
+            if dfa.alphabet.contains(&symbol) {
+                return Err("The alphabet contains a symbol more than once");
+            }
+            dfa.add_symbol(symbol);
+        }
+
+        for state in schema.accepts {
+            if !dfa.states.contains(&state) {
+                return Err("Accept state is not a valid state");
+            }
+            if !dfa.accept_states.contains(&state) {
+                dfa.add_accept_state(state);
+            }
+        }
+
+        for transition in schema.transitions {
+            dfa.add_transition(&(transition.from, transition.symbol), transition.to)?;
+        }
+
+        Ok(dfa)
+    }
+}
+
+impl<S: Symbol + Serialize> Serialize for Dfa<S> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        DfaSchema::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, S: Symbol + Deserialize<'de>> Deserialize<'de> for Dfa<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Dfa::try_from(DfaSchema::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dfa_serde_round_trip() {
+        let dfa = Dfa::from("(a|b)*abb").unwrap();
+
+        let json = serde_json::to_string(&dfa).unwrap();
+        let read: Dfa = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(dfa.states, read.states);
+        assert_eq!(dfa.alphabet, read.alphabet);
+        assert_eq!(dfa.transition_fn, read.transition_fn);
+        assert_eq!(dfa.accept_states, read.accept_states);
+        assert!(read.run("babb").unwrap());
+    }
+
+    #[test]
+    fn dfa_serde_validation() {
+        let parse = |json: &str| serde_json::from_str::<Dfa>(json);
+
+        assert_eq!(
+            r#"{"states":[0,1],"alphabet":["a"],"transitions":[{"from":0,"symbol":"a","to":1}],"start":0,"accepts":[1]}"#,
+            serde_json::to_string(
+                &parse(
+                    r#"{"states":[0,1],"alphabet":["a"],"start":0,"accepts":[1],
+                        "transitions":[{"from":0,"symbol":"a","to":1}]}"#
+                )
+                .unwrap()
+            )
+            .unwrap()
+        );
+        // two transitions on the same symbol
+        assert!(parse(
+            r#"{"states":[0,1],"alphabet":["a"],"start":0,"accepts":[1],
+                "transitions":[{"from":0,"symbol":"a","to":1},{"from":0,"symbol":"a","to":0}]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"states":[0,2],"alphabet":["a"],"start":0,"accepts":[],"transitions":[]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"states":[0],"alphabet":["a","a"],"start":0,"accepts":[],"transitions":[]}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,511 @@
+use super::Dfa;
+use crate::language::Symbol;
+use std::ops::Range;
+
+/// A single match of a dfa inside of a string.
+/// `start` and `end` are byte offsets into the searched string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<'t> {
+    text: &'t str,
+    start: usize,
+    end: usize,
+}
+
+impl<'t> Match<'t> {
+    pub(crate) fn new(text: &'t str, start: usize, end: usize) -> Self {
+        Match { text, start, end }
+    }
+
+    #[inline]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    #[inline]
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &'t str {
+        &self.text[self.range()]
+    }
+}
+
+/// An iterator over all non-overlapping leftmost-longest
+/// matches of a dfa inside of a string.
+///
+/// Each match is found with a single forwards pass that runs the dfa
+/// from every position at once, keeping only the leftmost run in each
+/// state. The pass stops as soon as none of the runs that could still
+/// give a leftmost-longest match are alive, so only as much of the
+/// string is read as the match needs.
+pub struct Matches<'d, 't> {
+    dfa: &'d Dfa,
+    text: &'t str,
+    position: usize,
+    last_end: Option<usize>,
+}
+
+impl<'d, 't> Matches<'d, 't> {
+    pub(super) fn new(dfa: &'d Dfa, text: &'t str) -> Self {
+        Matches {
+            dfa,
+            text,
+            position: 0,
+            last_end: None,
+        }
+    }
+}
+
+impl<'d, 't> Iterator for Matches<'d, 't> {
+    type Item = Match<'t>;
+
+    fn next(&mut self) -> Option<Match<'t>> {
+        while self.position <= self.text.len() {
+            let (start, end) = leftmost_longest(self.dfa, self.text, self.position)?;
+
+            // an empty match right after the previous match is skipped
+            if start == end && self.last_end == Some(end) {
+                self.position = start + next_char_len(self.text, start);
+                continue;
+            }
+
+            self.position = if start == end {
+                end + next_char_len(self.text, end)
+            } else {
+                end
+            };
+            self.last_end = Some(end);
+
+            return Some(Match {
+                text: self.text,
+                start,
+                end,
+            });
+        }
+
+        None
+    }
+}
+
+/// An iterator over the substrings of a string
+/// that are separated by matches of a dfa.
+pub struct Split<'d, 't> {
+    matches: Matches<'d, 't>,
+    text: &'t str,
+    last: usize,
+    finished: bool,
+}
+
+impl<'d, 't> Iterator for Split<'d, 't> {
+    type Item = &'t str;
+
+    fn next(&mut self) -> Option<&'t str> {
+        if self.finished {
+            return None;
+        }
+
+        match self.matches.next() {
+            Some(found) => {
+                let piece = &self.text[self.last..found.start()];
+                self.last = found.end();
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                Some(&self.text[self.last..])
+            }
+        }
+    }
+}
+
+impl Dfa {
+    /// Returns true if any substring of the given string
+    /// is accepted by the dfa.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from("ab*a").unwrap();
+    ///
+    /// assert!(dfa.is_match("xxabbbaxx"));
+    /// assert!(!dfa.is_match("xxabbbxx"));
+    /// ```
+    pub fn is_match(&self, text: &str) -> bool {
+        is_match(self, text)
+    }
+
+    /// Returns the leftmost-longest match inside of the given string
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from("ab*").unwrap();
+    /// let found = dfa.find("xxabbbab").unwrap();
+    ///
+    /// assert_eq!(2..6, found.range());
+    /// assert_eq!("abbb", found.as_str());
+    /// ```
+    pub fn find<'t>(&self, text: &'t str) -> Option<Match<'t>> {
+        self.find_iter(text).next()
+    }
+
+    /// Returns an iterator over all non-overlapping
+    /// leftmost-longest matches inside of the given string
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from("ab*").unwrap();
+    /// let found: Vec<&str> = dfa.find_iter("abbxaab").map(|m| m.as_str()).collect();
+    ///
+    /// assert_eq!(vec!["abb", "a", "ab"], found);
+    /// ```
+    pub fn find_iter<'d, 't>(&'d self, text: &'t str) -> Matches<'d, 't> {
+        Matches::new(self, text)
+    }
+
+    /// Replaces every match inside of the given string
+    /// with the replacement string, which is inserted literally.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from("a(b|c)*").unwrap();
+    ///
+    /// assert_eq!("x-y-z", dfa.replace_all("xabcyaz", "-"));
+    /// ```
+    pub fn replace_all(&self, text: &str, replacement: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+
+        for found in self.find_iter(text) {
+            result.push_str(&text[last..found.start()]);
+            result.push_str(replacement);
+            last = found.end();
+        }
+        result.push_str(&text[last..]);
+
+        result
+    }
+
+    /// Returns an iterator over the substrings of the given string
+    /// that are separated by matches.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Dfa;
+    ///
+    /// let dfa = Dfa::from(",( )*|;").unwrap();
+    /// let fields: Vec<&str> = dfa.split("a, b;;c,").collect();
+    ///
+    /// assert_eq!(vec!["a", "b", "", "c", ""], fields);
+    /// ```
+    pub fn split<'d, 't>(&'d self, text: &'t str) -> Split<'d, 't> {
+        Split {
+            matches: self.find_iter(text),
+            text,
+            last: 0,
+            finished: false,
+        }
+    }
+}
+
+/// A deterministic automata that can be searched for matches
+pub(crate) trait Search {
+    /// Returns a number greater than every state
+    fn state_bound(&self) -> usize;
+
+    fn start(&self) -> u32;
+
+    fn accepts(&self, state: u32) -> bool;
+
+    /// Returns true if no accept state can be reached from the state
+    fn is_dead(&self, state: u32) -> bool;
+
+    /// Returns the state after reading the symbol, if there is one
+    fn next(&self, state: u32, symbol: char) -> Option<u32>;
+}
+
+impl Search for Dfa {
+    fn state_bound(&self) -> usize {
+        self.live_states().len()
+    }
+
+    fn start(&self) -> u32 {
+        self.start_state()
+    }
+
+    fn accepts(&self, state: u32) -> bool {
+        self.is_accept_state(state)
+    }
+
+    fn is_dead(&self, state: u32) -> bool {
+        !self.live_states()[state as usize]
+    }
+
+    fn next(&self, state: u32, symbol: char) -> Option<u32> {
+        self.transition_fn.get(&(state, symbol)).copied()
+    }
+}
+
+/// Returns true if any substring of the given string is accepted
+pub(crate) fn is_match<A: Search>(automata: &A, text: &str) -> bool {
+    // simulate the automata prefixed with .* by tracking
+    // every state a match that started earlier could be in
+    let start = automata.start();
+    if automata.is_dead(start) {
+        return false;
+    }
+
+    let mut seen = vec![false; automata.state_bound()];
+    let mut current = vec![start];
+
+    for symbol in text.chars() {
+        if current.iter().any(|&state| automata.accepts(state)) {
+            return true;
+        }
+
+        seen.iter_mut().for_each(|seen| *seen = false);
+        seen[start as usize] = true;
+        let mut next = vec![start];
+        for state in current {
+            match automata.next(state, symbol) {
+                Some(target) if !automata.is_dead(target) && !seen[target as usize] => {
+                    seen[target as usize] = true;
+                    next.push(target);
+                }
+                _ => {}
+            }
+        }
+        current = next;
+    }
+
+    current.iter().any(|&state| automata.accepts(state))
+}
+
+/// Returns the start and end of the leftmost-longest match
+/// that starts at or after the byte offset `from`.
+///
+/// It runs the automata from every position at once, keeping only the
+/// leftmost run in each state, since every run in the same state reads
+/// the rest of the string the same way. It stops as soon as none of
+/// the runs that could still give the match are alive.
+pub(crate) fn leftmost_longest<A: Search>(
+    automata: &A,
+    text: &str,
+    from: usize,
+) -> Option<(usize, usize)> {
+    let start_state = automata.start();
+    if automata.is_dead(start_state) {
+        return None;
+    }
+
+    // runs as (state, start), ordered by their start
+    let mut runs: Vec<(u32, usize)> = Vec::new();
+    let mut seen = vec![false; automata.state_bound()];
+    let mut best: Option<(usize, usize)> = None;
+    let mut chars = text[from..].char_indices();
+    let mut position = from;
+
+    loop {
+        // a run that starts later than a found match can't win
+        if best.is_none() && runs.iter().all(|&(state, _)| state != start_state) {
+            runs.push((start_state, position));
+        }
+
+        // the first accepting run has the leftmost start
+        if let Some(&(_, start)) = runs.iter().find(|&&(state, _)| automata.accepts(state)) {
+            best = Some((start, position));
+            runs.retain(|&(_, other)| other <= start);
+        }
+
+        let symbol = match chars.next() {
+            Some((index, symbol)) if !runs.is_empty() => {
+                position = from + index + symbol.len_utf8();
+                symbol
+            }
+            _ => break,
+        };
+
+        seen.iter_mut().for_each(|seen| *seen = false);
+        let mut next = Vec::with_capacity(runs.len() + 1);
+        for &(state, start) in runs.iter() {
+            match automata.next(state, symbol) {
+                Some(target) if !automata.is_dead(target) && !seen[target as usize] => {
+                    seen[target as usize] = true;
+                    next.push((target, start));
+                }
+                _ => {}
+            }
+        }
+        runs = next;
+
+        if runs.is_empty() && best.is_some() {
+            break;
+        }
+    }
+
+    best
+}
+
+/// Returns the length of the char at the given byte offset,
+/// or 1 if the offset is at the end of the string
+fn next_char_len(text: &str, index: usize) -> usize {
+    text[index..].chars().next().map_or(1, char::len_utf8)
+}
+
+impl<S: Symbol> Dfa<S> {
+    /// Returns which states an accept state can be reached from,
+    /// indexed by state. It's computed once and kept until the dfa changes.
+    pub(super) fn live_states(&self) -> &[bool] {
+        self.live.get_or_init(|| live_states(self))
+    }
+}
+
+/// Marks every state from which an accept state can be reached
+fn live_states<S: Symbol>(dfa: &Dfa<S>) -> Vec<bool> {
+    let mut live = vec![false; state_table_len(dfa)];
+    dfa.accept_states
+        .iter()
+        .for_each(|&state| live[state as usize] = true);
+
+    let mut done = false;
+    while !done {
+        done = true;
+
+        for (&(source, _), &target) in dfa.transition_fn.iter() {
+            if live[target as usize] && !live[source as usize] {
+                live[source as usize] = true;
+                done = false;
+            }
+        }
+    }
+
+    live
+}
+
+/// Returns the length of a table that can be indexed by any state
+fn state_table_len<S: Symbol>(dfa: &Dfa<S>) -> usize {
+    dfa.states
+        .iter()
+        .max()
+        .map_or(0, |&state| state as usize + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::traits::*;
+
+    fn spans(dfa: &Dfa, text: &str) -> Vec<(usize, usize)> {
+        dfa.find_iter(text).map(|m| (m.start(), m.end())).collect()
+    }
+
+    #[test]
+    fn dfa_is_match() {
+        let dfa = Dfa::from("a|(ab|b)*").unwrap();
+        assert!(dfa.is_match(""));
+        assert!(dfa.is_match("ccc"));
+
+        let dfa = Dfa::from("ab(a|b)*c").unwrap();
+        assert!(dfa.is_match("ab c abbac"));
+        assert!(dfa.is_match("abc"));
+        assert!(!dfa.is_match("ab c ab ac"));
+        assert!(!dfa.is_match(""));
+    }
+
+    #[test]
+    fn dfa_find_leftmost_longest() {
+        let dfa = Dfa::from("abc|b").unwrap();
+        let found = dfa.find("xabc").unwrap();
+        assert_eq!((1, 4), (found.start(), found.end()));
+
+        let dfa = Dfa::from("a(b|c)*").unwrap();
+        let found = dfa.find("zzabcbcb-a").unwrap();
+        assert_eq!("abcbcb", found.as_str());
+
+        assert!(dfa.find("zzz").is_none());
+    }
+
+    #[test]
+    fn dfa_find_iter() {
+        let dfa = Dfa::from("ab|b").unwrap();
+        assert_eq!(vec![(0, 2), (2, 3), (4, 6)], spans(&dfa, "abbxab"));
+
+        let dfa = Dfa::from("(ab)*").unwrap();
+        assert_eq!(vec![(0, 4), (5, 7)], spans(&dfa, "ababxab"));
+        assert_eq!(vec![(0, 0)], spans(&dfa, ""));
+    }
+
+    #[test]
+    fn dfa_replace_all() {
+        let dfa = Dfa::from("ab|b").unwrap();
+        assert_eq!("[][]x[]", dfa.replace_all("abbxab", "[]"));
+        assert_eq!("xyz", dfa.replace_all("xyz", "[]"));
+        assert_eq!("", dfa.replace_all("", "[]"));
+
+        let dfa = Dfa::from("(ab)*").unwrap();
+        assert_eq!("-x-", dfa.replace_all("ababxab", "-"));
+    }
+
+    #[test]
+    fn dfa_split() {
+        let dfa = Dfa::from("--*").unwrap();
+        let pieces: Vec<&str> = dfa.split("a-b---c").collect();
+        assert_eq!(vec!["a", "b", "c"], pieces);
+
+        let pieces: Vec<&str> = dfa.split("-a-").collect();
+        assert_eq!(vec!["", "a", ""], pieces);
+
+        let pieces: Vec<&str> = dfa.split("").collect();
+        assert_eq!(vec![""], pieces);
+    }
+
+    #[test]
+    fn dfa_find_prefers_leftmost() {
+        // a run that starts earlier wins even if a later one accepts first
+        let dfa = Dfa::from("abcd|c").unwrap();
+        assert_eq!(vec![(0, 4), (6, 7)], spans(&dfa, "abcdabc"));
+
+        let dfa = Dfa::from("abcd|bc").unwrap();
+        assert_eq!(vec![(1, 3)], spans(&dfa, "abce"));
+        assert_eq!(vec![(0, 4)], spans(&dfa, "abcd"));
+    }
+
+    #[test]
+    fn dfa_live_states_cache() {
+        let mut dfa = Dfa::from("ab").unwrap();
+        assert!(dfa.find("xab").is_some());
+
+        // changing the dfa drops the cached live states
+        let dead = dfa.num_states() as u32;
+        dfa.add_state();
+        dfa.add_transition(&(dead, 'a'), dead).unwrap();
+        assert!(!dfa.live_states()[dead as usize]);
+        dfa.add_accept_state(dead);
+        assert!(dfa.live_states()[dead as usize]);
+    }
+
+    #[test]
+    fn dfa_find_iter_unicode() {
+        let dfa = Dfa::from("čć*").unwrap();
+        let found: Vec<&str> = dfa.find_iter("ačććžč").map(|m| m.as_str()).collect();
+        assert_eq!(vec!["čćć", "č"], found);
+    }
+}
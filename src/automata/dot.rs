@@ -0,0 +1,421 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+
+/// Direction in which graphviz lays out the states
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RankDirection {
+    #[default]
+    LeftToRight,
+    TopToBottom,
+    RightToLeft,
+    BottomToTop,
+}
+
+impl RankDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RankDirection::LeftToRight => "LR",
+            RankDirection::TopToBottom => "TB",
+            RankDirection::RightToLeft => "RL",
+            RankDirection::BottomToTop => "BT",
+        }
+    }
+}
+
+/// Options for the graphviz output of `Nfa::to_dot_with` and `Dfa::to_dot_with`
+///
+/// # Example
+/// ```rust
+/// use fmsi::automata::dot::{DotOptions, RankDirection};
+/// use fmsi::Nfa;
+///
+/// let nfa = Nfa::from("a|b").unwrap();
+/// let (dfa, sets) = nfa.to_dfa_with_state_sets();
+///
+/// let options = DotOptions {
+///     rank_direction: RankDirection::TopToBottom,
+///     state_labels: Some(sets.iter().map(|set| format!("{:?}", set)).collect()),
+/// };
+/// let dot = dfa.to_dot_with(&options);
+///
+/// assert!(dot.contains("rankdir=TB;"));
+/// assert!(dot.contains("0 [label=\"[0, 1, 2, 4, 5]\"];"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DotOptions {
+    pub rank_direction: RankDirection,
+    /// Labels of the states, in the order of the states,
+    /// the state numbers are used if there are none
+    pub state_labels: Option<Vec<String>>,
+}
+
+/// States and transitions of an automata, with the labels of
+/// all the transitions between two states merged in to one edge.
+/// Transitions on the empty string have no label.
+pub(crate) struct Graph {
+    pub(crate) states: Vec<u32>,
+    pub(crate) start_state: u32,
+    pub(crate) accept_states: Vec<u32>,
+    pub(crate) edges: BTreeMap<(u32, u32), Vec<Option<String>>>,
+}
+
+impl Graph {
+    pub(crate) fn new(states: &[u32], start_state: u32, accept_states: &[u32]) -> Self {
+        Graph {
+            states: states.to_vec(),
+            start_state,
+            accept_states: accept_states.to_vec(),
+            edges: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a transition, merging it with the others between the same states
+    pub(crate) fn add_edge(&mut self, source: u32, target: u32, label: Option<String>) {
+        let labels = self.edges.entry((source, target)).or_default();
+        labels.push(label);
+    }
+
+    /// Returns the edges with their labels sorted, formatted with the
+    /// given function and joined with `, `. Labels that could be mistaken
+    /// for the separator or the empty string are quoted first, see
+    /// `quote_label`, and the empty string is written as `epsilon`.
+    pub(crate) fn merged_edges<'a, F>(
+        &'a self,
+        format: F,
+        epsilon: &'a str,
+    ) -> impl Iterator<Item = (u32, u32, String)> + 'a
+    where
+        F: Fn(&str) -> String + 'a,
+    {
+        self.edges.iter().map(move |(&(source, target), labels)| {
+            let mut labels = labels.clone();
+            labels.sort();
+            labels.dedup();
+
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|label| match label {
+                    Some(label) => format(&quote_label(label)),
+                    None => epsilon.to_string(),
+                })
+                .collect();
+            (source, target, labels.join(", "))
+        })
+    }
+
+    pub(crate) fn to_dot(&self, options: &DotOptions) -> String {
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph {{").unwrap();
+        writeln!(dot, "    rankdir={};", options.rank_direction.as_str()).unwrap();
+        writeln!(dot, "    node [shape=circle];").unwrap();
+        writeln!(dot, "    __start [shape=point];").unwrap();
+        writeln!(dot, "    __start -> {};", self.start_state).unwrap();
+
+        for (index, state) in self.states.iter().enumerate() {
+            let mut attributes = Vec::new();
+            if self.accept_states.contains(state) {
+                attributes.push("shape=doublecircle".to_string());
+            }
+            if let Some(label) = options
+                .state_labels
+                .as_ref()
+                .and_then(|labels| labels.get(index))
+            {
+                attributes.push(format!("label=\"{}\"", escape(label)));
+            }
+
+            if attributes.is_empty() {
+                writeln!(dot, "    {};", state).unwrap();
+            } else {
+                writeln!(dot, "    {} [{}];", state, attributes.join(", ")).unwrap();
+            }
+        }
+
+        for (source, target, label) in self.merged_edges(escape, "ε") {
+            writeln!(dot, "    {} -> {} [label=\"{}\"];", source, target, label).unwrap();
+        }
+
+        dot.push('}');
+        dot.push('\n');
+        dot
+    }
+
+    /// Returns the states in layers by their distance from the start
+    /// state, states that can't be reached are in the last layer
+    fn layers(&self) -> Vec<Vec<u32>> {
+        let mut layer_of: BTreeMap<u32, usize> = BTreeMap::from([(self.start_state, 0)]);
+        let mut queue = VecDeque::from([self.start_state]);
+
+        while let Some(state) = queue.pop_front() {
+            let layer = layer_of[&state];
+            for &(_, target) in self.edges.keys().filter(|(source, _)| *source == state) {
+                if let Entry::Vacant(entry) = layer_of.entry(target) {
+                    entry.insert(layer + 1);
+                    queue.push_back(target);
+                }
+            }
+        }
+
+        let num_layers = layer_of.values().max().unwrap() + 1;
+        let mut layers = vec![Vec::new(); num_layers];
+        let mut unreachable = Vec::new();
+        for &state in self.states.iter() {
+            match layer_of.get(&state) {
+                Some(&layer) => layers[layer].push(state),
+                None => unreachable.push(state),
+            }
+        }
+        if !unreachable.is_empty() {
+            layers.push(unreachable);
+        }
+
+        layers
+    }
+
+    /// Returns the graph as a mermaid state diagram, laid out from
+    /// left to right, with the initial and final pseudo states marking
+    /// the start and accept states
+    pub(crate) fn to_mermaid(&self) -> String {
+        let mut mermaid = String::new();
+
+        writeln!(mermaid, "stateDiagram-v2").unwrap();
+        writeln!(mermaid, "    direction LR").unwrap();
+        writeln!(mermaid, "    classDef accepting stroke-width:4px").unwrap();
+        writeln!(mermaid, "    [*] --> q{}", self.start_state).unwrap();
+
+        for (source, target, label) in self.merged_edges(escape_mermaid, "ε") {
+            writeln!(mermaid, "    q{} --> q{} : {}", source, target, label).unwrap();
+        }
+        for state in self.accept_states.iter() {
+            writeln!(mermaid, "    q{} --> [*]", state).unwrap();
+        }
+        for state in self.accept_states.iter() {
+            writeln!(mermaid, "    class q{} accepting", state).unwrap();
+        }
+
+        mermaid
+    }
+
+    /// Returns the graph as a tikz picture, with the states of every
+    /// layer in a column. It needs the `automata` and `arrows.meta`
+    /// tikz libraries.
+    pub(crate) fn to_tikz(&self) -> String {
+        let mut tikz = String::new();
+
+        writeln!(
+            tikz,
+            "\\begin{{tikzpicture}}[shorten >=1pt, auto, >=Stealth, initial text=]"
+        )
+        .unwrap();
+
+        for (column, layer) in self.layers().iter().enumerate() {
+            for (row, state) in layer.iter().enumerate() {
+                let mut style = vec!["state"];
+                if *state == self.start_state {
+                    style.push("initial");
+                }
+                if self.accept_states.contains(state) {
+                    style.push("accepting");
+                }
+
+                writeln!(
+                    tikz,
+                    "    \\node[{}] (q{}) at ({}, {}) {{$q_{{{}}}$}};",
+                    style.join(", "),
+                    state,
+                    column as f64 * 2.5,
+                    0.0 - row as f64 * 2.0,
+                    state
+                )
+                .unwrap();
+            }
+        }
+
+        let edges: Vec<(u32, u32, String)> =
+            self.merged_edges(escape_tikz, "$\\varepsilon$").collect();
+        if !edges.is_empty() {
+            writeln!(tikz, "    \\path[->]").unwrap();
+        }
+        for (index, (source, target, label)) in edges.iter().enumerate() {
+            let style = if source == target {
+                "[loop above] "
+            } else if self.edges.contains_key(&(*target, *source)) {
+                "[bend left] "
+            } else {
+                ""
+            };
+            let end = if index + 1 == edges.len() { ";" } else { "" };
+
+            if source == target {
+                writeln!(
+                    tikz,
+                    "        (q{}) edge {}node {{{}}} (){}",
+                    source, style, label, end
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    tikz,
+                    "        (q{}) edge {}node {{{}}} (q{}){}",
+                    source, style, label, target, end
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(tikz, "\\end{{tikzpicture}}").unwrap();
+        tikz
+    }
+}
+
+/// Quotes a label like a rust literal, `','` or `'ε'`, if it contains
+/// a comma, whitespace, a single quote, a backslash or an `ε`, so that
+/// the labels of merged edges and the empty string can be told apart
+fn quote_label(label: &str) -> String {
+    if !label.contains(|c: char| matches!(c, ',' | '\'' | '\\' | 'ε') || c.is_whitespace()) {
+        return label.to_string();
+    }
+
+    let mut chars = label.chars();
+    match (chars.next(), chars.next()) {
+        (Some(symbol), None) => format!("{:?}", symbol),
+        _ => format!("{:?}", label),
+    }
+}
+
+/// Escapes quotes and backslashes in a graphviz string
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Replaces the chars that mermaid treats as syntax with entity codes
+fn escape_mermaid(label: &str) -> String {
+    label
+        .chars()
+        .map(|symbol| match symbol {
+            ':' | ';' | '#' | '"' | '<' | '>' | '%' => format!("#{};", symbol as u32),
+            symbol => symbol.to_string(),
+        })
+        .collect()
+}
+
+/// Escapes the special chars of LaTeX
+fn escape_tikz(label: &str) -> String {
+    label
+        .chars()
+        .map(|symbol| match symbol {
+            '\\' => "\\textbackslash{}".to_string(),
+            '~' => "\\textasciitilde{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            '{' | '}' | '$' | '&' | '#' | '_' | '%' => format!("\\{}", symbol),
+            symbol => symbol.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graph_merges_edges() {
+        let mut graph = Graph::new(&[0, 1], 0, &[1]);
+        graph.add_edge(0, 1, Some("b".to_string()));
+        graph.add_edge(0, 1, Some("a".to_string()));
+        graph.add_edge(1, 1, Some("\"".to_string()));
+
+        let edges: Vec<(u32, u32, String)> =
+            graph.merged_edges(|label| label.to_string(), "ε").collect();
+        assert_eq!(
+            vec![(0, 1, "a, b".to_string()), (1, 1, "\"".to_string())],
+            edges
+        );
+        assert!(graph
+            .to_dot(&DotOptions::default())
+            .contains("1 -> 1 [label=\"\\\"\"];"));
+    }
+
+    #[test]
+    fn graph_quotes_separators() {
+        let mut graph = Graph::new(&[0, 1], 0, &[1]);
+        for label in [",", " ", "a", "'", "\\", "x,y"] {
+            graph.add_edge(0, 1, Some(label.to_string()));
+        }
+        graph.add_edge(1, 1, Some("ε".to_string()));
+        graph.add_edge(1, 1, None);
+
+        let edges: Vec<(u32, u32, String)> =
+            graph.merged_edges(|label| label.to_string(), "ε").collect();
+        assert_eq!(
+            vec![
+                (0, 1, "' ', '\\'', ',', '\\\\', a, \"x,y\"".to_string()),
+                (1, 1, "ε, 'ε'".to_string())
+            ],
+            edges
+        );
+    }
+
+    fn sample() -> Graph {
+        let mut graph = Graph::new(&[0, 1, 2, 3], 0, &[2]);
+        graph.add_edge(0, 1, Some("b".to_string()));
+        graph.add_edge(0, 1, Some("a".to_string()));
+        graph.add_edge(0, 3, Some("c".to_string()));
+        graph.add_edge(1, 2, None);
+        graph.add_edge(2, 1, Some(":".to_string()));
+        graph.add_edge(2, 2, Some("_".to_string()));
+        graph
+    }
+
+    #[test]
+    fn graph_to_mermaid() {
+        assert_eq!(
+            "stateDiagram-v2\n    \
+             direction LR\n    \
+             classDef accepting stroke-width:4px\n    \
+             [*] --> q0\n    \
+             q0 --> q1 : a, b\n    \
+             q0 --> q3 : c\n    \
+             q1 --> q2 : ε\n    \
+             q2 --> q1 : #58;\n    \
+             q2 --> q2 : _\n    \
+             q2 --> [*]\n    \
+             class q2 accepting\n",
+            sample().to_mermaid()
+        );
+    }
+
+    #[test]
+    fn graph_to_tikz() {
+        let graph = sample();
+        assert_eq!(vec![vec![0], vec![1, 3], vec![2]], graph.layers());
+
+        assert_eq!(
+            "\\begin{tikzpicture}[shorten >=1pt, auto, >=Stealth, initial text=]\n    \
+             \\node[state, initial] (q0) at (0, 0) {$q_{0}$};\n    \
+             \\node[state] (q1) at (2.5, 0) {$q_{1}$};\n    \
+             \\node[state] (q3) at (2.5, -2) {$q_{3}$};\n    \
+             \\node[state, accepting] (q2) at (5, 0) {$q_{2}$};\n    \
+             \\path[->]\n        \
+             (q0) edge node {a, b} (q1)\n        \
+             (q0) edge node {c} (q3)\n        \
+             (q1) edge [bend left] node {$\\varepsilon$} (q2)\n        \
+             (q2) edge [bend left] node {:} (q1)\n        \
+             (q2) edge [loop above] node {\\_} ();\n\
+             \\end{tikzpicture}\n",
+            graph.to_tikz()
+        );
+    }
+
+    #[test]
+    fn graph_to_tikz_keeps_literal_epsilon() {
+        let mut graph = Graph::new(&[0, 1], 0, &[1]);
+        graph.add_edge(0, 1, Some("ε".to_string()));
+        graph.add_edge(0, 1, None);
+
+        assert!(graph
+            .to_tikz()
+            .contains("(q0) edge node {$\\varepsilon$, 'ε'} (q1);"));
+    }
+}
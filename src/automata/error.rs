@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::fmt;
+
+/// An error returned when an automata can't be built
+/// within the given limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// The regex isn't in the correct form
+    Syntax(&'static str),
+    /// The regex has more symbols and operators than the limit
+    RegexTooLarge { max_size: usize },
+    /// The dfa would have more states than the limit
+    TooManyStates { max_states: usize },
+    /// The construction would use more memory than the limit
+    TooMuchMemory { max_memory: usize },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Syntax(message) => write!(f, "{}", message),
+            BuildError::RegexTooLarge { max_size } => {
+                write!(f, "Regex has more than {} symbols and operators", max_size)
+            }
+            BuildError::TooManyStates { max_states } => {
+                write!(f, "The dfa has more than {} states", max_states)
+            }
+            BuildError::TooMuchMemory { max_memory } => {
+                write!(f, "The dfa needs more than {} bytes", max_memory)
+            }
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+impl From<&'static str> for BuildError {
+    fn from(message: &'static str) -> Self {
+        BuildError::Syntax(message)
+    }
+}
@@ -7,14 +7,17 @@ pub trait StateIter {
 }
 
 pub trait AlphabetIter {
-    fn alphabet_iter(&self) -> impl Iterator<Item = &char>;
-    fn alphabet_iter_mut(&mut self) -> impl Iterator<Item = &mut char>;
+    type Symbol;
+
+    fn alphabet_iter(&self) -> impl Iterator<Item = &Self::Symbol>;
+    fn alphabet_iter_mut(&mut self) -> impl Iterator<Item = &mut Self::Symbol>;
 }
 
 pub trait TransitionIter {
+    type Source;
     type Target;
 
-    fn transitions_iter(&self) -> impl Iterator<Item = (&(u32, char), &Self::Target)>;
-    fn transitions_iter_mut(&mut self) -> impl Iterator<Item = (&(u32, char), &mut Self::Target)>;
-    fn get_transition(&self, key: (u32, char)) -> Option<&Self::Target>;
+    fn transitions_iter(&self) -> impl Iterator<Item = (&Self::Source, &Self::Target)>;
+    fn transitions_iter_mut(&mut self) -> impl Iterator<Item = (&Self::Source, &mut Self::Target)>;
+    fn get_transition(&self, key: Self::Source) -> Option<&Self::Target>;
 }
@@ -0,0 +1,455 @@
+//! Reading and writing of finite automata in the XML format of JFLAP.
+//!
+//! JFLAP states can have any ids, so they are renumbered when read:
+//! the initial state becomes state 0, and the others follow in the
+//! order of their ids. Lambda transitions, the ones with an empty
+//! `<read/>`, become transitions on the empty string.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Positions of the states in the JFLAP editor
+pub type Coordinates = BTreeMap<u32, (f64, f64)>;
+
+/// A finite automata read from, or about to be written to a JFLAP file,
+/// with the states numbered from 0 and state 0 as the initial state
+#[derive(Debug, Default)]
+pub(crate) struct Jflap {
+    pub(crate) num_states: u32,
+    pub(crate) accept_states: Vec<u32>,
+    pub(crate) transitions: Vec<(u32, Option<char>, u32)>,
+    pub(crate) coordinates: Coordinates,
+}
+
+impl Jflap {
+    /// Returns the symbols used in the transitions, sorted
+    pub(crate) fn alphabet(&self) -> Vec<char> {
+        let mut alphabet: Vec<char> = self
+            .transitions
+            .iter()
+            .filter_map(|(_, symbol, _)| *symbol)
+            .collect();
+        alphabet.sort();
+        alphabet.dedup();
+        alphabet
+    }
+
+    pub(crate) fn parse(input: &str) -> Result<Self, &'static str> {
+        let root = Parser::new(input).document()?;
+        if root.name != "structure" {
+            return Err("A JFLAP file must have a structure element");
+        }
+        if let Some(kind) = root.child("type") {
+            if kind.text.trim() != "fa" {
+                return Err("Only finite automata JFLAP files are supported");
+            }
+        }
+
+        // older versions of JFLAP put the states directly in the structure
+        let automaton = root.child("automaton").unwrap_or(&root);
+
+        let mut ids = Vec::new();
+        let mut initial = None;
+        for state in automaton.children("state") {
+            let id = state.id()?;
+            if state.child("initial").is_some() {
+                if initial.is_some() {
+                    return Err("A JFLAP automaton can have only one initial state");
+                }
+                initial = Some(id);
+            }
+            ids.push(id);
+        }
+        let Some(initial) = initial else {
+            return Err("A JFLAP automaton must have an initial state");
+        };
+
+        ids.sort();
+        ids.dedup();
+        let mut lookup_table: BTreeMap<u32, u32> = BTreeMap::from([(initial, 0)]);
+        for &id in ids.iter().filter(|&&id| id != initial) {
+            lookup_table.insert(id, lookup_table.len() as u32);
+        }
+
+        let mut jflap = Jflap {
+            num_states: lookup_table.len() as u32,
+            ..Jflap::default()
+        };
+
+        for element in automaton.children("state") {
+            let state = lookup_table[&element.id()?];
+
+            if element.child("final").is_some() && !jflap.accept_states.contains(&state) {
+                jflap.accept_states.push(state);
+            }
+
+            let x = element.child("x").map(|x| x.text.trim().parse::<f64>());
+            let y = element.child("y").map(|y| y.text.trim().parse::<f64>());
+            if let (Some(x), Some(y)) = (x, y) {
+                let (Ok(x), Ok(y)) = (x, y) else {
+                    return Err("JFLAP state coordinates must be numbers");
+                };
+                jflap.coordinates.insert(state, (x, y));
+            }
+        }
+        jflap.accept_states.sort();
+
+        for element in automaton.children("transition") {
+            let state = |name: &str| -> Result<u32, &'static str> {
+                let id = element
+                    .child(name)
+                    .ok_or("A JFLAP transition must have a from and a to state")?
+                    .text
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| "JFLAP state ids must be numbers")?;
+                lookup_table
+                    .get(&id)
+                    .copied()
+                    .ok_or("A JFLAP transition uses a state that doesn't exist")
+            };
+
+            let source = state("from")?;
+            let target = state("to")?;
+
+            let read = element.child("read").map(|read| read.text.as_str());
+            let mut chars = read.unwrap_or_default().chars();
+            let symbol = match (chars.next(), chars.next()) {
+                (symbol, None) => symbol,
+                _ => return Err("A JFLAP transition can read only a single symbol"),
+            };
+
+            jflap.transitions.push((source, symbol, target));
+        }
+
+        Ok(jflap)
+    }
+
+    /// Writes the automata as a JFLAP file, states without
+    /// coordinates are placed on a line
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = String::new();
+
+        writeln!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>"
+        )
+        .unwrap();
+        writeln!(xml, "<structure>").unwrap();
+        writeln!(xml, "\t<type>fa</type>").unwrap();
+        writeln!(xml, "\t<automaton>").unwrap();
+
+        for state in 0..self.num_states {
+            let (x, y) = self
+                .coordinates
+                .get(&state)
+                .copied()
+                .unwrap_or((100.0 + 120.0 * state as f64, 100.0));
+
+            writeln!(xml, "\t\t<state id=\"{}\" name=\"q{}\">", state, state).unwrap();
+            writeln!(xml, "\t\t\t<x>{:?}</x>", x).unwrap();
+            writeln!(xml, "\t\t\t<y>{:?}</y>", y).unwrap();
+            if state == 0 {
+                writeln!(xml, "\t\t\t<initial/>").unwrap();
+            }
+            if self.accept_states.contains(&state) {
+                writeln!(xml, "\t\t\t<final/>").unwrap();
+            }
+            writeln!(xml, "\t\t</state>").unwrap();
+        }
+
+        let mut transitions = self.transitions.clone();
+        transitions.sort();
+        for (source, symbol, target) in transitions {
+            writeln!(xml, "\t\t<transition>").unwrap();
+            writeln!(xml, "\t\t\t<from>{}</from>", source).unwrap();
+            writeln!(xml, "\t\t\t<to>{}</to>", target).unwrap();
+            match symbol {
+                Some(symbol) => {
+                    writeln!(xml, "\t\t\t<read>{}</read>", escape(&symbol.to_string())).unwrap()
+                }
+                None => writeln!(xml, "\t\t\t<read/>").unwrap(),
+            }
+            writeln!(xml, "\t\t</transition>").unwrap();
+        }
+
+        writeln!(xml, "\t</automaton>").unwrap();
+        writeln!(xml, "</structure>").unwrap();
+        xml
+    }
+}
+
+/// An XML element, with the text of all it's text nodes joined
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the id of a JFLAP state
+    fn id(&self) -> Result<u32, &'static str> {
+        self.attribute("id")
+            .ok_or("A JFLAP state must have an id")?
+            .trim()
+            .parse()
+            .map_err(|_| "JFLAP state ids must be numbers")
+    }
+}
+
+/// A parser for the subset of XML used by JFLAP: elements, attributes,
+/// text, comments and the declaration, without namespaces or CDATA
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser { input, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    /// Skips whitespace, comments, the declaration and doctypes
+    fn skip_misc(&mut self) -> Result<(), &'static str> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+
+            let end = if trimmed.starts_with("<!--") {
+                "-->"
+            } else if trimmed.starts_with("<?") {
+                "?>"
+            } else if trimmed.starts_with("<!") {
+                ">"
+            } else {
+                return Ok(());
+            };
+
+            let length = trimmed.find(end).ok_or("Unterminated XML markup")?;
+            self.position += length + end.len();
+        }
+    }
+
+    fn document(&mut self) -> Result<Element, &'static str> {
+        self.skip_misc()?;
+        let root = self.element()?;
+        self.skip_misc()?;
+
+        if !self.rest().is_empty() {
+            return Err("Unexpected content after the XML root element");
+        }
+        Ok(root)
+    }
+
+    fn name(&mut self) -> Result<String, &'static str> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(rest.len());
+
+        if length == 0 {
+            return Err("Expected an XML name");
+        }
+        self.position += length;
+        Ok(rest[..length].to_string())
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), &'static str> {
+        let rest = self.rest().trim_start();
+        if !rest.starts_with(expected) {
+            return Err("Malformed XML element");
+        }
+        self.position = self.input.len() - rest.len() + expected.len();
+        Ok(())
+    }
+
+    fn element(&mut self) -> Result<Element, &'static str> {
+        self.expect("<")?;
+        let mut element = Element {
+            name: self.name()?,
+            ..Element::default()
+        };
+
+        loop {
+            let rest = self.rest().trim_start();
+            self.position = self.input.len() - rest.len();
+
+            if rest.starts_with("/>") {
+                self.position += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.position += 1;
+                break;
+            }
+
+            let key = self.name()?;
+            self.expect("=")?;
+            let rest = self.rest().trim_start();
+            let quote = rest.chars().next().ok_or("Unterminated XML element")?;
+            if quote != '"' && quote != '\'' {
+                return Err("XML attribute values must be quoted");
+            }
+            let length = rest[1..]
+                .find(quote)
+                .ok_or("Unterminated XML attribute value")?;
+            let value = unescape(&rest[1..1 + length])?;
+            self.position = self.input.len() - rest.len() + length + 2;
+
+            element.attributes.push((key, value));
+        }
+
+        loop {
+            let rest = self.rest();
+            let length = rest.find('<').ok_or("Unterminated XML element")?;
+            element.text.push_str(&unescape(&rest[..length])?);
+            self.position += length;
+
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.position += 2;
+                if self.name()? != element.name {
+                    return Err("XML closing tag doesn't match the opening tag");
+                }
+                self.expect(">")?;
+                return Ok(element);
+            }
+            if rest.starts_with("<!--") {
+                let length = rest.find("-->").ok_or("Unterminated XML markup")?;
+                self.position += length + 3;
+                continue;
+            }
+
+            element.children.push(self.element()?);
+        }
+    }
+}
+
+/// Replaces the predefined and numeric XML entities
+fn unescape(text: &str) -> Result<String, &'static str> {
+    let mut result = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let end = rest[start..].find(';').ok_or("Unterminated XML entity")? + start;
+
+        let symbol = match &rest[start + 1..end] {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            entity => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32).ok_or("Unknown XML entity")?
+            }
+        };
+
+        result.push(symbol);
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Escapes the chars that can't appear in XML text
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jflap_parse() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><!--Created with JFLAP 7.1.--><structure>
+	<type>fa</type>
+	<automaton>
+		<!--The list of states.-->
+		<state id="3" name="q3">
+			<x>247.0</x>
+			<y>130.5</y>
+			<final/>
+		</state>
+		<state id="7" name="q7">
+			<x>101.0</x>
+			<y>119.0</y>
+			<initial/>
+		</state>
+		<!--The list of transitions.-->
+		<transition>
+			<from>7</from>
+			<to>3</to>
+			<read>&lt;</read>
+		</transition>
+		<transition>
+			<from>3</from>
+			<to>7</to>
+			<read/>
+		</transition>
+	</automaton>
+</structure>"#;
+
+        let jflap = Jflap::parse(input).unwrap();
+
+        assert_eq!(2, jflap.num_states);
+        assert_eq!(vec![1], jflap.accept_states);
+        assert_eq!(vec![(0, Some('<'), 1), (1, None, 0)], jflap.transitions);
+        assert_eq!(Some(&(101.0, 119.0)), jflap.coordinates.get(&0));
+        assert_eq!(Some(&(247.0, 130.5)), jflap.coordinates.get(&1));
+
+        let written = Jflap::parse(&jflap.to_xml()).unwrap();
+        assert_eq!(jflap.transitions, written.transitions);
+        assert_eq!(jflap.coordinates, written.coordinates);
+    }
+
+    #[test]
+    fn jflap_parse_errors() {
+        assert!(Jflap::parse("<structure><type>fa</type></structure>").is_err());
+        assert!(Jflap::parse("<structure><type>pda</type></structure>").is_err());
+        assert!(Jflap::parse("<structure><state id=\"0\"><initial/></state>").is_err());
+        assert!(Jflap::parse(
+            "<structure><state id=\"0\"><initial/></state>\
+             <transition><from>0</from><to>0</to><read>ab</read></transition></structure>"
+        )
+        .is_err());
+        assert!(Jflap::parse(
+            "<structure><state id=\"0\"><initial/></state>\
+             <transition><from>0</from><to>1</to><read>a</read></transition></structure>"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use crate::automata::iters::*;
+use crate::nfa::Nfa;
+use crate::util;
+use std::collections::HashMap;
+use std::mem::{size_of, size_of_val};
+
+/// Memory budget used by `LazyDfa::new`, in bytes
+pub const DEFAULT_MEMORY_BUDGET: usize = 1 << 20;
+
+/// Number of times the cache can be cleared during a single run
+/// before the lazy dfa gives up and falls back to nfa simulation
+pub const MAX_CACHE_CLEARS: usize = 8;
+
+/// A dfa that gets determinized on demand while processing input.
+///
+/// Every dfa state is a set of nfa states. States and transitions
+/// are computed the first time they are needed and cached for later
+/// runs. When the cache outgrows its memory budget, it gets cleared,
+/// and if that happens too often during a single run, the rest of the
+/// input is processed by simulating the nfa directly.
+///
+/// # Example
+/// ```rust
+/// use fmsi::LazyDfa;
+///
+/// let mut dfa = LazyDfa::from("a|(ab|b)*").unwrap();
+///
+/// assert!(dfa.run("abbab"));
+/// assert!(!dfa.run("aa"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LazyDfa {
+    nfa: Nfa,
+    memory_budget: usize,
+    memory_usage: usize,
+    cache_clears: usize,
+    state_sets: Vec<Vec<u32>>,
+    accepting: Vec<bool>,
+    lookup_table: HashMap<Vec<u32>, u32>,
+    transition_fn: HashMap<(u32, char), u32>,
+}
+
+impl LazyDfa {
+    /// Creates a lazy dfa with the default memory budget
+    pub fn new(nfa: Nfa) -> Self {
+        LazyDfa::with_memory_budget(nfa, DEFAULT_MEMORY_BUDGET)
+    }
+
+    /// Creates a lazy dfa whose cache can use
+    /// roughly `memory_budget` bytes
+    pub fn with_memory_budget(nfa: Nfa, memory_budget: usize) -> Self {
+        LazyDfa {
+            nfa,
+            memory_budget,
+            memory_usage: 0,
+            cache_clears: 0,
+            state_sets: Vec::new(),
+            accepting: Vec::new(),
+            lookup_table: HashMap::new(),
+            transition_fn: HashMap::new(),
+        }
+    }
+
+    /// Constructor returns a lazy dfa for the given regex
+    pub fn from(regex: &str) -> Result<Self, &'static str> {
+        Ok(LazyDfa::new(Nfa::from(regex)?))
+    }
+
+    #[inline]
+    pub fn nfa(&self) -> &Nfa {
+        &self.nfa
+    }
+
+    /// Returns the number of dfa states that are currently cached
+    pub fn num_cached_states(&self) -> usize {
+        self.state_sets.len()
+    }
+
+    /// Returns the estimated number of bytes used by the cache
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
+    /// Returns how many times the cache was cleared in total
+    pub fn cache_clears(&self) -> usize {
+        self.cache_clears
+    }
+
+    /// Removes every cached state and transition
+    pub fn clear_cache(&mut self) {
+        self.state_sets.clear();
+        self.accepting.clear();
+        self.lookup_table.clear();
+        self.transition_fn.clear();
+        self.memory_usage = 0;
+    }
+
+    /// Processes the given string and returns true
+    /// if it ends up in an accept state.
+    pub fn run(&mut self, input: &str) -> bool {
+        let start = util::state_epsilon_clojure(&self.nfa, self.nfa.start_state());
+        let mut clears = 0;
+
+        let mut current = match self.cache_state(&start) {
+            Some(state) => state,
+            None => {
+                self.clear_cache();
+                self.cache_clears += 1;
+
+                match self.cache_state(&start) {
+                    Some(state) => state,
+                    // not even a single state fits in to the budget
+                    None => return self.nfa.run_from(start, input),
+                }
+            }
+        };
+
+        for (index, symbol) in input.char_indices() {
+            if let Some(&next) = self.transition_fn.get(&(current, symbol)) {
+                current = next;
+                continue;
+            }
+
+            let set = self.nfa.step(&self.state_sets[current as usize], symbol);
+
+            current = match self.cache_state(&set) {
+                Some(next) => {
+                    self.transition_fn.insert((current, symbol), next);
+                    next
+                }
+                None => {
+                    let rest = &input[index + symbol.len_utf8()..];
+                    clears += 1;
+
+                    // the cache is thrashing, so determinizing
+                    // doesn't pay off anymore
+                    if clears > MAX_CACHE_CLEARS {
+                        return self.nfa.run_from(set, rest);
+                    }
+
+                    self.clear_cache();
+                    self.cache_clears += 1;
+
+                    match self.cache_state(&set) {
+                        Some(next) => next,
+                        None => return self.nfa.run_from(set, rest),
+                    }
+                }
+            };
+        }
+
+        self.accepting[current as usize]
+    }
+
+    /// Returns the cached state for the given set of nfa states,
+    /// caching it first if needed. Returns None if the state
+    /// doesn't fit in to the memory budget.
+    fn cache_state(&mut self, set: &[u32]) -> Option<u32> {
+        if let Some(&state) = self.lookup_table.get(set) {
+            return Some(state);
+        }
+
+        let cost = self.state_cost(set);
+        if self.memory_usage + cost > self.memory_budget {
+            return None;
+        }
+
+        let state = self.state_sets.len() as u32;
+        self.state_sets.push(set.to_vec());
+        self.accepting
+            .push(set.iter().any(|&s| self.nfa.is_accept_state(s)));
+        self.lookup_table.insert(set.to_vec(), state);
+        self.memory_usage += cost;
+
+        Some(state)
+    }
+
+    /// Estimates the number of bytes a state takes up in the cache,
+    /// including the room for all of it's outgoing transitions
+    fn state_cost(&self, set: &[u32]) -> usize {
+        // the set is stored twice, once as a state and once as a key
+        let set_size = 2 * (size_of::<Vec<u32>>() + size_of_val(set));
+        let transitions_size = self.nfa.alphabet_iter().count() * size_of::<((u32, char), u32)>();
+
+        set_size + size_of::<u32>() + size_of::<bool>() + transitions_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lazy_dfa_run() {
+        let mut dfa = LazyDfa::from("a|(ab|b)*").unwrap();
+
+        assert!(dfa.run(""));
+        assert!(dfa.run("a"));
+        assert!(dfa.run("abbab"));
+        assert!(!dfa.run("aa"));
+        assert!(!dfa.run("ba"));
+        assert!(!dfa.run("abc"));
+    }
+
+    #[test]
+    fn lazy_dfa_caches_states() {
+        let mut dfa = LazyDfa::from("a*b*").unwrap();
+        assert_eq!(0, dfa.num_cached_states());
+
+        assert!(dfa.run("aab"));
+        let cached = dfa.num_cached_states();
+        assert!(cached > 0);
+
+        // the same input doesn't add any new states
+        assert!(dfa.run("aab"));
+        assert_eq!(cached, dfa.num_cached_states());
+        assert_eq!(0, dfa.cache_clears());
+    }
+
+    #[test]
+    fn lazy_dfa_small_budget() {
+        let nfa = Nfa::from("a|(ab|b)*").unwrap();
+        let mut lazy = LazyDfa::with_memory_budget(nfa.clone(), 300);
+
+        for input in ["", "a", "ab", "abbab", "bbbbbbbbbbab", "aa", "ba", "abba"] {
+            assert_eq!(nfa.run(input), lazy.run(input), "input: {:?}", input);
+            assert!(lazy.memory_usage() <= 300);
+        }
+        assert!(lazy.cache_clears() > 0);
+    }
+
+    #[test]
+    fn lazy_dfa_nfa_fallback() {
+        let nfa = Nfa::from("a|(ab|b)*").unwrap();
+        let mut lazy = LazyDfa::with_memory_budget(nfa.clone(), 0);
+
+        assert_eq!(0, lazy.num_cached_states());
+        assert!(lazy.run("abbab"));
+        assert!(!lazy.run("aa"));
+        assert_eq!(0, lazy.num_cached_states());
+    }
+}
@@ -1,33 +1,58 @@
+mod antimirov;
+mod att;
+mod determinize;
+mod glushkov;
+mod jflap;
 mod operators;
+#[cfg(feature = "serde")]
+mod schema;
+mod transform;
+
+pub use crate::automata::error::BuildError;
+pub use determinize::DeterminizeOptions;
 
 use super::dfa::Dfa;
+use super::dot::{DotOptions, Graph};
+use super::table::{self, Table};
+use determinize::Determinizer;
 pub use crate::automata::iters::*;
 use crate::automata::traits::*;
-use crate::language::EMPTY_STRING;
+use crate::language::{Regex, Symbol};
 use crate::util;
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
 use std::default::Default;
-
+use std::fmt;
+
+/// A non-deterministic finite automata over symbols of type `S`.
+///
+/// Transitions are labeled with `Some(symbol)`,
+/// or with `None` for transitions on the empty string.
+///
+/// With the `serde` feature, an nfa is serialized as a map of
+/// `states` (consecutive numbers), `alphabet`, `transitions` (a list
+/// of `{ from, symbol, to }`, with a `null` symbol for transitions on
+/// the empty string), `start` (the first state) and `accepts`.
+/// Deserialization fails if the parts don't form a valid nfa.
 #[derive(Debug, Clone)]
-pub struct Nfa {
+pub struct Nfa<S = char> {
     states: Vec<u32>,
-    alphabet: Vec<char>,
-    transition_fn: HashMap<(u32, char), Vec<u32>>,
+    alphabet: Vec<S>,
+    transition_fn: HashMap<(u32, Option<S>), Vec<u32>>,
     accept_states: Vec<u32>,
 }
 
-impl Default for Nfa {
+impl<S: Symbol> Default for Nfa<S> {
     fn default() -> Self {
         Nfa {
             states: vec![0],
-            alphabet: vec![EMPTY_STRING],
+            alphabet: Vec::new(),
             transition_fn: HashMap::new(),
             accept_states: Vec::new(),
         }
     }
 }
 
-impl State for Nfa {
+impl<S: Symbol> State for Nfa<S> {
     fn add_state(&mut self) {
         let last = self.states.last().unwrap();
         self.states.push(last + 1);
@@ -54,29 +79,36 @@ impl State for Nfa {
     }
 }
 
-impl Alphabet for Nfa {
-    fn add_symbol(&mut self, symbol: char) {
+impl<S: Symbol> Alphabet for Nfa<S> {
+    type Symbol = S;
+
+    fn add_symbol(&mut self, symbol: S) {
         if !self.alphabet.contains(&symbol) {
             self.alphabet.push(symbol);
         }
     }
 
-    fn remove_symbol(&mut self, symbol: char) {
+    fn remove_symbol(&mut self, symbol: S) {
         if let Some(position) = self.alphabet.iter().position(|e| *e == symbol) {
             self.alphabet.remove(position);
         }
 
-        self.transition_fn.retain(|&(_, s), _| s != symbol);
+        self.transition_fn
+            .retain(|(_, s), _| s.as_ref() != Some(&symbol));
     }
 }
 
-impl Transition for Nfa {
-    fn add_transition(&mut self, source: &(u32, char), target: u32) -> Result<(), &'static str> {
+impl<S: Symbol> Transition for Nfa<S> {
+    type Source = (u32, Option<S>);
+
+    fn add_transition(&mut self, source: &(u32, Option<S>), target: u32) -> Result<(), &'static str> {
         if !self.states.contains(&source.0) {
             return Err("Source state is not a valid state");
         }
-        if !self.alphabet.contains(&source.1) {
-            return Err("Transition symbol is not in the alphabet");
+        if let Some(symbol) = &source.1 {
+            if !self.alphabet.contains(symbol) {
+                return Err("Transition symbol is not in the alphabet");
+            }
         }
         if !self.states.contains(&target) {
             return Err("Destination state is not a valid state");
@@ -90,14 +122,14 @@ impl Transition for Nfa {
                 }
             }
             None => {
-                self.transition_fn.insert(*source, vec![target]);
+                self.transition_fn.insert(source.clone(), vec![target]);
             }
         }
 
         Ok(())
     }
     //
-    // fn remove_transition(&mut self, source: &(u32, char), target: u32) {
+    // fn remove_transition(&mut self, source: &(u32, Option<S>), target: u32) {
     //     if let Some(destinations) = self.transition_fn.get_mut(source) {
     //         if let Some(index) = destinations.iter().position(|&e| e == target) {
     //             destinations.remove(index);
@@ -110,116 +142,215 @@ impl Transition for Nfa {
     // }
 }
 
-impl TransitionIter for Nfa {
+impl<S: Symbol> AlphabetIter for Nfa<S> {
+    type Symbol = S;
+
+    #[inline]
+    fn alphabet_iter(&self) -> impl Iterator<Item = &S> {
+        self.alphabet.iter()
+    }
+
+    #[inline]
+    fn alphabet_iter_mut(&mut self) -> impl Iterator<Item = &mut S> {
+        self.alphabet.iter_mut()
+    }
+}
+
+impl<S: Symbol> TransitionIter for Nfa<S> {
+    type Source = (u32, Option<S>);
     type Target = Vec<u32>;
 
     #[inline]
-    fn transitions_iter(&self) -> impl Iterator<Item = (&(u32, char), &Vec<u32>)> {
+    fn transitions_iter(&self) -> impl Iterator<Item = (&(u32, Option<S>), &Vec<u32>)> {
         self.transition_fn.iter()
     }
 
     #[inline]
-    fn transitions_iter_mut(&mut self) -> impl Iterator<Item = (&(u32, char), &mut Vec<u32>)> {
+    fn transitions_iter_mut(&mut self) -> impl Iterator<Item = (&(u32, Option<S>), &mut Vec<u32>)> {
         self.transition_fn.iter_mut()
     }
 
-    fn get_transition(&self, key: (u32, char)) -> Option<&Vec<u32>> {
+    fn get_transition(&self, key: (u32, Option<S>)) -> Option<&Vec<u32>> {
         self.transition_fn.get(&key)
     }
 }
 
 impl Nfa {
-    pub fn from(expression: &str) -> Result<Self, &'static str> {
-        util::check_for_correctness(expression)?;
-
-        enum OnReturn {
-            Union,
-            Bracket,
-            Concat,
-            Finished,
+    /// Constructor returns an nfa from a given regex, or an error
+    /// if `Regex::size` of the parsed regex is more than `max_size`.
+    /// The number of states and transitions grows with that size,
+    /// so this bounds the size of the nfa.
+    pub fn from_with_limit(expression: &str, max_size: usize) -> Result<Self, BuildError> {
+        let regex = Regex::parse(expression)?;
+        if regex.size() > max_size {
+            return Err(BuildError::RegexTooLarge { max_size });
         }
 
-        let mut stack: Vec<(Nfa, OnReturn)> = vec![(Nfa::new(), OnReturn::Finished)];
-        let err_msg = "Regex is not in the correct form";
+        Ok(Nfa::from_regex(&regex)?)
+    }
 
-        for token in expression.chars() {
-            let current = stack.last_mut().unwrap();
+    /// Constructor returns an nfa from a given regex, in the
+    /// syntax accepted by `Regex::parse`. The empty regex
+    /// and empty brackets match the empty string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::from("[a-c]x*|()").unwrap();
+    ///
+    /// assert!(nfa.run("bxx"));
+    /// assert!(nfa.run(""));
+    /// assert!(!nfa.run("x"));
+    /// ```
+    pub fn from(expression: &str) -> Result<Self, &'static str> {
+        Nfa::from_regex(&Regex::parse(expression)?)
+    }
 
-            match token {
-                '(' => {
-                    stack.push((Nfa::new(), OnReturn::Bracket));
-                }
-                ')' => loop {
-                    let last = stack.pop().expect(err_msg);
-                    match last.1 {
-                        OnReturn::Union => {
-                            let new_last = stack.last_mut().expect(err_msg);
-                            operators::union(&mut new_last.0, last.0)?;
-                        }
-                        OnReturn::Bracket => {
-                            let new_last = stack.last_mut().expect(err_msg);
-                            operators::concat(&mut new_last.0, last.0)?;
-                            break;
-                        }
-                        OnReturn::Concat => {
-                            let new_last = stack.last_mut().expect(err_msg);
-                            operators::concat(&mut new_last.0, last.0)?;
+    /// Builds an nfa from a parsed regex with the thompson construction.
+    /// Returns an error if a class has too many chars to be expanded
+    /// in to the alphabet.
+    pub fn from_regex(regex: &Regex) -> Result<Self, &'static str> {
+        match regex {
+            Regex::Union(..) => {
+                // a|b|c is built as a|(b|c)
+                let mut alternatives = Vec::new();
+                let mut stack = vec![regex];
+                while let Some(regex) = stack.pop() {
+                    match regex {
+                        Regex::Union(first, second) => {
+                            stack.push(second);
+                            stack.push(first);
                         }
-                        OnReturn::Finished => return Ok(last.0.clone()),
+                        regex => alternatives.push(Nfa::from_concat(regex)?),
                     }
-                },
-                '*' => {
-                    operators::kleene_star(&mut current.0)?;
                 }
-                '|' => {
-                    // first concatenate previous nfa's on the stack
-                    // if they should be concatnated
-                    // and then push a new one to the stack
-                    loop {
-                        let last = stack.pop().expect(err_msg);
-                        match last.1 {
-                            OnReturn::Concat => {
-                                let previous = stack.last_mut().expect(err_msg);
-                                operators::concat(&mut previous.0, last.0)?;
-                            }
-                            _ => {
-                                stack.push(last);
-                                break;
-                            }
-                        }
-                    }
-                    stack.push((Nfa::new(), OnReturn::Union));
-                }
-                alpha => {
-                    let mut new = Nfa::new();
-                    new.add_symbol(alpha);
-                    new.push_symbol(alpha)?;
-                    stack.push((new, OnReturn::Concat));
+
+                let mut nfa = alternatives.pop().unwrap();
+                while let Some(mut alternative) = alternatives.pop() {
+                    operators::union(&mut alternative, nfa)?;
+                    nfa = alternative;
                 }
+                Ok(nfa)
             }
+            regex => Nfa::from_concat(regex),
+        }
+    }
+
+    /// Builds an alternative of a union by concatenating all of it's
+    /// parts on to a new nfa, or on to the first part if it's a bracket
+    fn from_concat(regex: &Regex) -> Result<Self, &'static str> {
+        let mut parts = Vec::new();
+        let mut current = regex;
+        while let Regex::Concat(first, second) = current {
+            parts.push(second.as_ref());
+            current = first;
         }
+        parts.push(current);
 
-        while let Some(current) = stack.pop() {
-            match current.1 {
-                OnReturn::Bracket => {
-                    return Err(err_msg);
-                }
-                OnReturn::Union => {
-                    let new_last = stack.last_mut().expect(err_msg);
-                    operators::union(&mut new_last.0, current.0)?;
-                }
-                OnReturn::Concat => {
-                    let new_last = stack.last_mut().expect(err_msg);
-                    operators::concat(&mut new_last.0, current.0)?;
+        if parts.contains(&&Regex::EmptySet) {
+            return Ok(Nfa::new());
+        }
+
+        let mut nfa = if is_bracket(current) {
+            parts.pop();
+            Nfa::from_part(current)?
+        } else {
+            Nfa::new()
+        };
+        for part in parts.into_iter().rev() {
+            operators::concat(&mut nfa, Nfa::from_part(part)?)?;
+        }
+        Ok(nfa)
+    }
+
+    /// Builds a single part of a concatenation
+    fn from_part(regex: &Regex) -> Result<Self, &'static str> {
+        let mut nfa = Nfa::new();
+
+        match regex {
+            Regex::EmptySet => {}
+            Regex::Symbol(_) | Regex::Class(_) => {
+                nfa.add_state();
+                nfa.add_accept_state(1);
+                for symbol in regex.chars()? {
+                    nfa.add_symbol(symbol);
+                    nfa.add_transition(&(0, Some(symbol)), 1)?;
                 }
-                OnReturn::Finished => return Ok(current.0),
+            }
+            Regex::KleeneStar(inner) => {
+                nfa = Nfa::from_part(inner)?;
+                operators::kleene_star(&mut nfa)?;
+            }
+            Regex::EmptyString => {
+                let mut inner = Nfa::new();
+                inner.add_accept_state(0);
+                operators::concat(&mut nfa, inner)?;
+            }
+            Regex::Concat(..) | Regex::Union(..) => {
+                operators::concat(&mut nfa, Nfa::from_regex(regex)?)?;
             }
         }
 
-        Err(err_msg)
+        Ok(nfa)
+    }
+
+    /// Processes the given string by simulating the nfa directly,
+    /// without converting it to a dfa first, and returns true
+    /// if the final set of states contains an accept state.
+    pub fn run(&self, input: &str) -> bool {
+        self.run_symbols(input.chars())
     }
 
-    fn new() -> Self {
+    /// Reads an nfa from a plain text transition table,
+    /// in the format written by it's `Display` implementation
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::from_table(
+    ///     "      | a        | ε
+    ///      ->q0  | {q0, q1} | ∅
+    ///      q1    | ∅        | q2
+    ///      *q2   | ∅        | ∅",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(nfa.run("aa"));
+    /// assert!(!nfa.run(""));
+    /// assert_eq!(nfa.to_string(), Nfa::from_table(&nfa.to_string()).unwrap().to_string());
+    /// ```
+    pub fn from_table(input: &str) -> Result<Self, &'static str> {
+        let table = Table::parse(input)?;
+
+        let mut nfa = Nfa::new();
+        (1..table.num_states).for_each(|_| nfa.add_state());
+        table
+            .alphabet
+            .into_iter()
+            .for_each(|symbol| nfa.add_symbol(symbol));
+        table
+            .accept_states
+            .into_iter()
+            .for_each(|state| nfa.add_accept_state(state));
+
+        for (source, symbol, target) in table.transitions {
+            nfa.add_transition(&(source, symbol), target)?;
+        }
+
+        Ok(nfa)
+    }
+
+    /// Continues the simulation of the nfa from a given set of states.
+    pub(crate) fn run_from(&self, current: Vec<u32>, input: &str) -> bool {
+        self.run_symbols_from(current, input.chars())
+    }
+}
+
+impl<S: Symbol> Nfa<S> {
+    /// Creates an nfa with a single state and an empty alphabet
+    pub fn new() -> Self {
         Nfa::default()
     }
 
@@ -239,7 +370,7 @@ impl Nfa {
 
     /// Increments state names by a given increment
     fn increment_states(&mut self, increment: u32) {
-        let mut lookup_table: HashMap<(u32, char), Vec<u32>> = HashMap::new();
+        let mut lookup_table: HashMap<(u32, Option<S>), Vec<u32>> = HashMap::new();
 
         // increment names in the states and accept states sets
         self.states.iter_mut().for_each(|e| *e += increment);
@@ -249,7 +380,7 @@ impl Nfa {
         // (because of the borrow checker)
         // and increement them by increemetn
         self.transition_fn.iter().for_each(|(key, value)| {
-            lookup_table.insert((key.0 + increment, key.1), value.clone());
+            lookup_table.insert((key.0 + increment, key.1.clone()), value.clone());
         });
 
         for entry in lookup_table.iter_mut() {
@@ -259,142 +390,230 @@ impl Nfa {
         self.transition_fn = lookup_table;
     }
 
-    fn is_accept_state(&self, state: u32) -> bool {
+    pub(crate) fn is_accept_state(&self, state: u32) -> bool {
         self.accept_states.contains(&state)
     }
 
-    fn push_symbol(&mut self, symbol: char) -> Result<(), &'static str> {
-        if !self.alphabet.contains(&symbol) {
-            return Err("The symbol is not in the alphabet");
-        }
-
-        let prev_last = self.last_added_state();
-        self.add_state();
-        let new_last = self.last_added_state();
+    /// Processes the given sequence of symbols by simulating the nfa
+    /// and returns true if the final set of states contains an accept state.
+    pub fn run_symbols<I: IntoIterator<Item = S>>(&self, input: I) -> bool {
+        let start = util::state_epsilon_clojure(self, self.start_state());
+        self.run_symbols_from(start, input)
+    }
 
-        if self.accept_states.is_empty() {
-            self.add_transition(&(prev_last, symbol), new_last)?;
-        } else {
-            for state in self.accept_states.clone() {
-                self.add_transition(&(state, symbol), new_last)?;
+    fn run_symbols_from<I: IntoIterator<Item = S>>(&self, mut current: Vec<u32>, input: I) -> bool {
+        for symbol in input {
+            // once the set is empty, it stays empty
+            if current.is_empty() {
+                return false;
             }
-            self.accept_states.clear();
+            current = self.step(&current, symbol);
         }
-        self.add_accept_state(new_last);
 
-        Ok(())
+        current.iter().any(|&state| self.is_accept_state(state))
     }
 
-    pub fn to_dfa(&self) -> Dfa {
-        let mut dfa = Dfa::new();
-        let mut queue: VecDeque<Vec<u32>> = VecDeque::new();
-        let mut states: Vec<Vec<u32>> = Vec::new();
-        let mut transitions: HashMap<(Vec<u32>, char), Vec<u32>> = HashMap::new();
-        let alphabet: Vec<char> = self
-            .alphabet
-            .iter()
-            .filter(|&s| *s != EMPTY_STRING)
-            .cloned()
-            .collect();
+    /// Returns the epsilon clojure of the set the given set
+    /// transitions to for the given symbol.
+    pub(crate) fn step(&self, set: &[u32], symbol: S) -> Vec<u32> {
+        let next = util::set_transitions(self, set, symbol);
+        util::set_epsilon_clojure(self, &next)
+    }
 
-        // add the epsilon clojure of the start state to the new_states and the queue
-        states.push(util::state_epsilon_clojure(self, self.start_state()));
-        queue.push_back(states.first().unwrap().clone());
-
-        while let Some(current) = queue.pop_front() {
-            // for every symbol in the alphabet
-            for symbol in alphabet.iter() {
-                // get every state that can be transitioned to
-                // from the current set of states
-                // and calculate an epsilon clojure on it
-                let new_tr = util::set_transitions(self, &current, *symbol);
-                let new_tr = util::set_epsilon_clojure(self, &new_tr);
-
-                // insert the transition for it
-                transitions.insert((current.clone(), *symbol), new_tr.clone());
-
-                // if it's a new state, add it to the set of state sets
-                // and push it to the queue
-                if !states.contains(&new_tr) {
-                    queue.push_back(new_tr.clone());
-                    states.push(new_tr);
-                }
-            }
-        }
+    /// Splits the alphabet in to classes of symbols that
+    /// transition every state to the same set of states.
+    /// Such symbols can't be told apart by the nfa.
+    pub fn symbol_classes(&self) -> Vec<Vec<S>> {
+        util::group_symbols(&self.alphabet, |symbol| {
+            self.states
+                .iter()
+                .map(|&state| self.get_transition((state, Some(symbol.clone()))))
+                .collect::<Vec<Option<&Vec<u32>>>>()
+        })
+    }
+
+    /// Converts the nfa to a complete dfa using the subset construction
+    pub fn to_dfa(&self) -> Dfa<S> {
+        // without limits the construction can't fail
+        Determinizer::new(self)
+            .run(&DeterminizeOptions::default())
+            .unwrap()
+            .0
+    }
+
+    /// Converts the nfa to a complete dfa, or returns an error
+    /// if the construction goes over one of the given limits
+    pub fn to_dfa_with(&self, options: &DeterminizeOptions) -> Result<Dfa<S>, BuildError> {
+        Ok(Determinizer::new(self).run(options)?.0)
+    }
 
-        // lookup table used for translating state sets to states
-        let mut lookup_table: HashMap<&Vec<u32>, u32> = HashMap::new();
-        for (index, state) in states.iter().enumerate() {
-            lookup_table.insert(state, index as u32);
-            dfa.add_state();
+    /// Converts the nfa to a complete dfa, and returns it along with
+    /// the set of nfa states behind every dfa state
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::from("ab").unwrap();
+    /// let (dfa, sets) = nfa.to_dfa_with_state_sets();
+    ///
+    /// assert_eq!(dfa.num_states(), sets.len());
+    /// assert!(sets[0].contains(&nfa.start_state()));
+    /// ```
+    pub fn to_dfa_with_state_sets(&self) -> (Dfa<S>, Vec<Vec<u32>>) {
+        let determinizer = Determinizer::new(self);
+        let (dfa, sets) = determinizer.run(&DeterminizeOptions::default()).unwrap();
+        let sets = sets.iter().map(|set| determinizer.states(set)).collect();
+
+        (dfa, sets)
+    }
+}
+
+impl<S: Symbol + fmt::Display> Nfa<S> {
+    /// Returns the graph of the nfa, transitions on
+    /// the empty string have no label
+    pub(crate) fn graph(&self) -> Graph {
+        let mut graph = Graph::new(&self.states, self.start_state(), &self.accept_states);
 
-            if state.iter().any(|&state| self.is_accept_state(state)) {
-                dfa.add_accept_state(index as u32);
+        for ((source, symbol), targets) in self.transition_fn.iter() {
+            let label = symbol.as_ref().map(|symbol| symbol.to_string());
+            for &target in targets {
+                graph.add_edge(*source, target, label.clone());
             }
         }
-        dfa.remove_state();
 
-        // add the alphabet to the dfa
-        alphabet.iter().for_each(|&s| dfa.add_symbol(s));
+        graph
+    }
 
-        // add transitions to the dfa
-        for ((source, symbol), destination) in transitions.iter() {
-            let source = lookup_table.get(source).unwrap();
-            let destination = lookup_table.get(destination).unwrap();
+    /// Returns the nfa in the graphviz dot format
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::from("a*").unwrap();
+    /// let dot = nfa.to_dot();
+    ///
+    /// assert!(dot.starts_with("digraph {"));
+    /// assert!(dot.contains("[label=\"ε\"]"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with(&DotOptions::default())
+    }
 
-            dfa.add_transition(&(*source, *symbol), *destination)
-                .unwrap();
-        }
+    /// Returns the nfa in the graphviz dot format, with the given options
+    pub fn to_dot_with(&self, options: &DotOptions) -> String {
+        self.graph().to_dot(options)
+    }
 
-        dfa
+    /// Returns the nfa as a mermaid state diagram, for markdown documents
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::glushkov("a|b").unwrap();
+    /// let mermaid = nfa.to_mermaid();
+    ///
+    /// assert!(mermaid.starts_with("stateDiagram-v2"));
+    /// assert!(mermaid.contains("q0 --> q1 : a"));
+    /// assert!(mermaid.contains("q1 --> [*]"));
+    /// ```
+    pub fn to_mermaid(&self) -> String {
+        self.graph().to_mermaid()
     }
-}
 
-pub mod test_utils {
-    use super::*;
+    /// Returns the nfa as a tikz picture, for LaTeX documents.
+    /// The document needs `\usetikzlibrary{automata, arrows.meta}`.
+    pub fn to_tikz(&self) -> String {
+        self.graph().to_tikz()
+    }
+}
 
-    pub fn prepare_nfa() -> Nfa {
-        let mut nfa = Nfa::new();
+impl<S: Symbol + fmt::Display> fmt::Display for Nfa<S> {
+    /// Writes the transition table of the nfa, with a row for every state
+    /// and a column for every symbol in sorted order, plus an `ε` column
+    /// if there are transitions on the empty string. Missing entries
+    /// are shown as `∅`, and the symbols `ε` and `|` and whitespace
+    /// are escaped like `\u{7c}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut symbols: Vec<Option<S>> = self.alphabet.iter().cloned().map(Some).collect();
+        symbols.sort();
+        if self.transition_fn.keys().any(|(_, symbol)| symbol.is_none()) {
+            symbols.push(None);
+        }
 
-        nfa.add_state();
-        nfa.add_state();
-        nfa.add_state();
+        let mut header = vec![String::new()];
+        header.extend(symbols.iter().map(|symbol| match symbol {
+            Some(symbol) => table::symbol_cell(&symbol.to_string()),
+            None => "ε".to_string(),
+        }));
+
+        let mut states = self.states.clone();
+        states.sort();
+
+        let mut rows = vec![header];
+        for state in states {
+            let mut row = vec![table::row_label(
+                state,
+                state == self.start_state(),
+                self.is_accept_state(state),
+            )];
+            row.extend(symbols.iter().map(|symbol| {
+                let targets = self
+                    .transition_fn
+                    .get(&(state, symbol.clone()))
+                    .map(|targets| targets.as_slice())
+                    .unwrap_or_default();
+                table::set_cell(targets)
+            }));
+            rows.push(row);
+        }
 
-        nfa.add_symbol('A');
-        nfa.add_symbol('B');
-        nfa.add_symbol('C');
+        table::write_table(f, &rows)
+    }
+}
 
-        nfa.add_transition(&(0, 'A'), 0).unwrap();
-        nfa.add_transition(&(0, 'A'), 1).unwrap();
-        nfa.add_transition(&(0, 'B'), 3).unwrap();
-        nfa.add_transition(&(0, 'C'), 1).unwrap();
-        nfa.add_transition(&(1, 'C'), 2).unwrap();
-        nfa.add_transition(&(2, 'B'), 3).unwrap();
-        nfa.add_transition(&(2, 'B'), 1).unwrap();
+/// Returns true if the part of a concatenation has to
+/// come from brackets, like `(ab)`, `(a|b)*` or `()`
+fn is_bracket(regex: &Regex) -> bool {
+    match regex {
+        Regex::EmptyString | Regex::Concat(..) | Regex::Union(..) => true,
+        Regex::KleeneStar(inner) => is_bracket(inner),
+        _ => false,
+    }
+}
 
-        nfa.add_accept_state(3);
+pub mod test_utils {
+    use super::*;
 
-        nfa
+    pub fn prepare_nfa() -> Nfa {
+        Nfa::from_table(
+            "     | A      | B      | C
+             →  0 | {0, 1} | {3}    | {1}
+                1 | ∅      | ∅      | {2}
+                2 | ∅      | {1, 3} | ∅
+              * 3 | ∅      | ∅      | ∅",
+        )
+        .unwrap()
     }
 
     pub fn prepare_nfa_pair() -> (Nfa, Nfa) {
-        let mut first = Nfa::new();
-        let mut second = Nfa::new();
-
-        first.add_state();
-        first.add_state();
-        first.add_state();
-        first.add_symbol('a');
-        first.add_symbol('b');
-        first.add_transition(&(0, 'a'), 1).unwrap();
-        first.add_transition(&(1, EMPTY_STRING), 2).unwrap();
-        first.add_transition(&(2, 'b'), 3).unwrap();
-        first.add_accept_state(3);
-
-        second.add_state();
-        second.add_symbol('a');
-        second.add_transition(&(0, 'a'), 1).unwrap();
-        second.add_accept_state(1);
+        let first = Nfa::from_table(
+            "     | a   | b   | ε
+             →  0 | {1} | ∅   | ∅
+                1 | ∅   | ∅   | {2}
+                2 | ∅   | {3} | ∅
+              * 3 | ∅   | ∅   | ∅",
+        )
+        .unwrap();
+
+        let second = Nfa::from_table(
+            "     | a
+             →  0 | {1}
+              * 1 | ∅",
+        )
+        .unwrap();
 
         (first, second)
     }
@@ -403,28 +622,30 @@ pub mod test_utils {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::automata::dot::RankDirection;
+    use crate::language::EMPTY_STRING;
 
     #[test]
     fn nfa_construction() {
         // regex: "a|(ab|b)*
         // {
         //     states: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
-        //     alphabet: ['\0', 'a', 'b'],
+        //     alphabet: ['a', 'b'],
         //     transition_fn: {
-        //         (1, '\0'): [2],
-        //         (2, 'a'): [3],
-        //         (4, '\0'): [5],
-        //         (0, '\0'): [1, 4],
-        //         (5, '\0'): [6],
-        //         (6, '\0'): [7, 12],
-        //         (13, 'b'): [14],
-        //         (11, '\0'): [5],
-        //         (9, '\0'): [10],
-        //         (14, '\0'): [5],
-        //         (12, '\0'): [13],
-        //         (10, 'b'): [11],
-        //         (7, '\0'): [8],
-        //         (8, 'a'): [9]
+        //         (1, None): [2],
+        //         (2, Some('a')): [3],
+        //         (4, None): [5],
+        //         (0, None): [1, 4],
+        //         (5, None): [6],
+        //         (6, None): [7, 12],
+        //         (13, Some('b')): [14],
+        //         (11, None): [5],
+        //         (9, None): [10],
+        //         (14, None): [5],
+        //         (12, None): [13],
+        //         (10, Some('b')): [11],
+        //         (7, None): [8],
+        //         (8, Some('a')): [9]
         //     },
         //     accept_states: [3, 4, 11, 14]
         // }
@@ -437,23 +658,23 @@ mod tests {
         };
 
         assert_eq!(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14], nfa.states);
-        assert_eq!(vec![EMPTY_STRING, 'a', 'b'], nfa.alphabet);
+        assert_eq!(vec!['a', 'b'], nfa.alphabet);
         assert_eq!(vec![3, 4, 11, 14], nfa.accept_states);
 
-        let mut map: HashMap<(u32, char), Vec<u32>> = HashMap::new();
+        let mut map: HashMap<(u32, Option<char>), Vec<u32>> = HashMap::new();
         map.insert((0, EMPTY_STRING), vec![1, 4]);
         map.insert((1, EMPTY_STRING), vec![2]);
-        map.insert((2, 'a'), vec![3]);
+        map.insert((2, Some('a')), vec![3]);
         map.insert((4, EMPTY_STRING), vec![5]);
         map.insert((5, EMPTY_STRING), vec![6]);
         map.insert((6, EMPTY_STRING), vec![7, 12]);
         map.insert((7, EMPTY_STRING), vec![8]);
-        map.insert((8, 'a'), vec![9]);
+        map.insert((8, Some('a')), vec![9]);
         map.insert((9, EMPTY_STRING), vec![10]);
-        map.insert((10, 'b'), vec![11]);
+        map.insert((10, Some('b')), vec![11]);
         map.insert((11, EMPTY_STRING), vec![5]);
         map.insert((12, EMPTY_STRING), vec![13]);
-        map.insert((13, 'b'), vec![14]);
+        map.insert((13, Some('b')), vec![14]);
         map.insert((14, EMPTY_STRING), vec![5]);
 
         for (key, value) in map.iter() {
@@ -463,41 +684,41 @@ mod tests {
         }
 
         let pairs = [
-            (0, 'a'),
-            (0, 'b'),
-            (1, 'a'),
-            (1, 'b'),
+            (0, Some('a')),
+            (0, Some('b')),
+            (1, Some('a')),
+            (1, Some('b')),
             (2, EMPTY_STRING),
-            (2, 'b'),
+            (2, Some('b')),
             (3, EMPTY_STRING),
-            (3, 'a'),
-            (3, 'b'),
-            (4, 'a'),
-            (4, 'b'),
-            (5, 'a'),
-            (5, 'b'),
-            (6, 'a'),
-            (6, 'b'),
-            (7, 'a'),
-            (7, 'b'),
+            (3, Some('a')),
+            (3, Some('b')),
+            (4, Some('a')),
+            (4, Some('b')),
+            (5, Some('a')),
+            (5, Some('b')),
+            (6, Some('a')),
+            (6, Some('b')),
+            (7, Some('a')),
+            (7, Some('b')),
             (8, EMPTY_STRING),
-            (8, 'b'),
-            (9, 'a'),
-            (9, 'b'),
-            (10, 'a'),
+            (8, Some('b')),
+            (9, Some('a')),
+            (9, Some('b')),
+            (10, Some('a')),
             (10, EMPTY_STRING),
-            (11, 'a'),
-            (11, 'b'),
-            (12, 'a'),
-            (12, 'b'),
+            (11, Some('a')),
+            (11, Some('b')),
+            (12, Some('a')),
+            (12, Some('b')),
             (13, EMPTY_STRING),
-            (13, 'a'),
-            (14, 'a'),
-            (14, 'b'),
+            (13, Some('a')),
+            (14, Some('a')),
+            (14, Some('b')),
         ];
 
         for i in pairs {
-            if nfa.transition_fn.get(&i).is_some() {
+            if nfa.transition_fn.contains_key(&i) {
                 panic!("Some transitions exist that shouldn't");
             }
         }
@@ -524,7 +745,7 @@ mod tests {
     fn nfa_adding_symbol() {
         let nfa = test_utils::prepare_nfa();
 
-        assert_eq!(vec![EMPTY_STRING, 'A', 'B', 'C'], nfa.alphabet);
+        assert_eq!(vec!['A', 'B', 'C'], nfa.alphabet);
     }
 
     #[test]
@@ -533,31 +754,31 @@ mod tests {
 
         nfa.remove_symbol('B');
 
-        assert_eq!(vec![EMPTY_STRING, 'A', 'C'], nfa.alphabet);
+        assert_eq!(vec!['A', 'C'], nfa.alphabet);
     }
 
     #[test]
     fn nfa_adding_transition() {
         let nfa = test_utils::prepare_nfa();
 
-        assert_eq!(vec![0, 1], *nfa.transition_fn.get(&(0, 'A')).unwrap());
-        assert_eq!(vec![3], *nfa.transition_fn.get(&(0, 'B')).unwrap());
-        assert_eq!(vec![1], *nfa.transition_fn.get(&(0, 'C')).unwrap());
-        assert_eq!(vec![2], *nfa.transition_fn.get(&(1, 'C')).unwrap());
-        assert_eq!(vec![1, 3], *nfa.transition_fn.get(&(2, 'B')).unwrap());
+        assert_eq!(vec![0, 1], *nfa.transition_fn.get(&(0, Some('A'))).unwrap());
+        assert_eq!(vec![3], *nfa.transition_fn.get(&(0, Some('B'))).unwrap());
+        assert_eq!(vec![1], *nfa.transition_fn.get(&(0, Some('C'))).unwrap());
+        assert_eq!(vec![2], *nfa.transition_fn.get(&(1, Some('C'))).unwrap());
+        assert_eq!(vec![1, 3], *nfa.transition_fn.get(&(2, Some('B'))).unwrap());
     }
 
     // #[test]
     // fn nfa_removing_transition() {
     //     let mut nfa = test_utils::prepare_nfa();
     //
-    //     nfa.remove_transition(&(0, 'A'), 1);
-    //     nfa.remove_transition(&(1, 'C'), 2);
-    //     nfa.remove_transition(&(2, 'B'), 1);
+    //     nfa.remove_transition(&(0, Some('A')), 1);
+    //     nfa.remove_transition(&(1, Some('C')), 2);
+    //     nfa.remove_transition(&(2, Some('B')), 1);
     //
-    //     assert_eq!(vec![0], *nfa.transition_fn.get(&(0, 'A')).unwrap());
-    //     assert_eq!(Option::None, nfa.transition_fn.get(&(1, 'C')));
-    //     assert_eq!(vec![3], *nfa.transition_fn.get(&(2, 'B')).unwrap());
+    //     assert_eq!(vec![0], *nfa.transition_fn.get(&(0, Some('A'))).unwrap());
+    //     assert_eq!(Option::None, nfa.transition_fn.get(&(1, Some('C'))));
+    //     assert_eq!(vec![3], *nfa.transition_fn.get(&(2, Some('B'))).unwrap());
     // }
 
     #[test]
@@ -578,4 +799,220 @@ mod tests {
         println!("{:?}", dfa);
         assert!(dfa.is_complete());
     }
+
+    #[test]
+    fn nfa_symbol_classes() {
+        let nfa = Nfa::from("a|(ab|b)*").unwrap();
+        assert_eq!(vec![vec!['a'], vec!['b']], nfa.symbol_classes());
+
+        // 'x', 'y' and 'z' can't be told apart
+        let mut nfa: Nfa = Nfa::new();
+        nfa.add_state();
+        for symbol in ['z', 'a', 'y', 'x'] {
+            nfa.add_symbol(symbol);
+        }
+        nfa.add_accept_state(1);
+        nfa.add_transition(&(0, Some('a')), 0).unwrap();
+        for symbol in ['x', 'y', 'z'] {
+            nfa.add_transition(&(0, Some(symbol)), 1).unwrap();
+            nfa.add_transition(&(0, Some(symbol)), 0).unwrap();
+        }
+        nfa.add_transition(&(1, None), 0).unwrap();
+
+        assert_eq!(vec![vec!['a'], vec!['x', 'y', 'z']], nfa.symbol_classes());
+
+        let dfa = nfa.to_dfa();
+        assert!(dfa.is_complete());
+        assert!(dfa.run("aaz").unwrap());
+        assert_eq!(nfa.run("xa"), dfa.run("xa").unwrap());
+    }
+
+    #[test]
+    fn nfa_from_regex_syntax() {
+        let nfa = Nfa::from("").unwrap();
+        assert!(nfa.run(""));
+        assert!(!nfa.run("a"));
+
+        let nfa = Nfa::from("a()b|[0-9]**").unwrap();
+        assert!(nfa.run("ab"));
+        assert!(nfa.run("42"));
+        assert!(!nfa.run("a"));
+
+        for expression in ["|", "a|", "*a", "(a|)", "a(|b)"] {
+            assert!(Nfa::from(expression).is_err(), "{}", expression);
+        }
+        assert!(Nfa::from("a.").is_err());
+    }
+
+    #[test]
+    fn nfa_from_with_limit() {
+        assert!(Nfa::from_with_limit("a|(ab|b)*", 8).is_ok());
+        assert_eq!(
+            Some(BuildError::RegexTooLarge { max_size: 7 }),
+            Nfa::from_with_limit("a|(ab|b)*", 7).err()
+        );
+
+        // a short class with a lot of chars
+        assert_eq!(
+            Some(BuildError::RegexTooLarge { max_size: 1000 }),
+            Nfa::from_with_limit("[\\u{0}-\\u{fff}]", 1000).err()
+        );
+        assert!(Nfa::from_with_limit("[\\u{0}-\\u{fff}]", 4096).is_ok());
+        assert_eq!(
+            Some(BuildError::Syntax("Brackets don't match")),
+            Nfa::from_with_limit("a|(ab|b*", 9).err()
+        );
+    }
+
+    #[test]
+    fn nfa_display() {
+        let nfa = Nfa::from("a|b*").unwrap();
+
+        assert_eq!(
+            "     | a   | b   | ε\n\
+             →  0 | ∅   | ∅   | {1, 4}\n\
+             \x20  1 | ∅   | ∅   | {2}\n\
+             \x20  2 | {3} | ∅   | ∅\n\
+             \x20* 3 | ∅   | ∅   | ∅\n\
+             \x20  4 | ∅   | ∅   | {5}\n\
+             \x20* 5 | ∅   | ∅   | {6}\n\
+             \x20  6 | ∅   | {7} | ∅\n\
+             \x20* 7 | ∅   | ∅   | {6}\n",
+            nfa.to_string()
+        );
+
+        // without transitions on the empty string there is no ε column
+        assert_eq!(
+            "     | a\n→* 0 | {1}\n * 1 | {1}\n",
+            Nfa::glushkov("a*").unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn nfa_from_table() {
+        let nfa = test_utils::prepare_nfa();
+
+        assert_eq!(vec![0, 1, 2, 3], nfa.states);
+        assert_eq!(vec!['A', 'B', 'C'], nfa.alphabet);
+        assert_eq!(vec![3], nfa.accept_states);
+        assert_eq!(Some(&vec![0, 1]), nfa.get_transition((0, Some('A'))));
+        assert_eq!(Some(&vec![1, 3]), nfa.get_transition((2, Some('B'))));
+        assert_eq!(7, nfa.transitions_iter().map(|(_, t)| t.len()).sum::<usize>());
+
+        // symbols that look like the separators or the ε column
+        let expressions = [
+            "a|(ab|b)*",
+            "(ab)*",
+            "a*b*",
+            "(a|b)*abb",
+            "-",
+            "+",
+            "+-",
+            "(+|-)*ε",
+            "a\\|b c",
+        ];
+        for expression in expressions {
+            let nfa = Nfa::from(expression).unwrap();
+            let read = Nfa::from_table(&nfa.to_string()).unwrap();
+
+            assert_eq!(nfa.states, read.states);
+            assert_eq!(nfa.accept_states, read.accept_states);
+            assert_eq!(nfa.transition_fn, read.transition_fn);
+        }
+    }
+
+    #[test]
+    fn nfa_to_dot() {
+        let nfa = Nfa::from("a*").unwrap();
+        let dot = nfa.to_dot_with(&DotOptions {
+            rank_direction: RankDirection::TopToBottom,
+            state_labels: None,
+        });
+
+        assert!(dot.contains("rankdir=TB;"));
+        assert!(dot.contains("__start -> 0;"));
+        assert!(dot.contains("[label=\"ε\"]"));
+        assert!(dot.contains("[label=\"a\"]"));
+
+        // a literal ε is quoted, so it can't be mistaken for the empty string
+        let dot = Nfa::from("ε*").unwrap().to_dot();
+        assert!(dot.contains("[label=\"ε\"]"));
+        assert!(dot.contains("[label=\"'ε'\"]"));
+        for state in nfa.accept_states.iter() {
+            assert!(dot.contains(&format!("{} [shape=doublecircle];", state)));
+        }
+    }
+
+    #[test]
+    fn nfa_to_dfa_state_sets_dot() {
+        let nfa = Nfa::from("a|b").unwrap();
+        let (dfa, sets) = nfa.to_dfa_with_state_sets();
+
+        assert_eq!(dfa.num_states(), sets.len());
+        let mut clojure = util::state_epsilon_clojure(&nfa, 0);
+        clojure.sort();
+        assert_eq!(clojure, sets[0]);
+
+        let labels: Vec<String> = sets
+            .iter()
+            .map(|set| {
+                let states: Vec<String> = set.iter().map(|state| state.to_string()).collect();
+                format!("{{{}}}", states.join(", "))
+            })
+            .collect();
+        let dot = dfa.to_dot_with(&DotOptions {
+            state_labels: Some(labels.clone()),
+            ..DotOptions::default()
+        });
+
+        for (state, label) in labels.iter().enumerate() {
+            assert!(dot.contains(&format!("{} [", state)));
+            assert!(dot.contains(&format!("label=\"{}\"", label)));
+        }
+    }
+
+    #[test]
+    fn nfa_run() {
+        let nfa = Nfa::from("a|(ab|b)*").unwrap();
+
+        assert!(nfa.run(""));
+        assert!(nfa.run("a"));
+        assert!(nfa.run("ab"));
+        assert!(nfa.run("bbab"));
+        assert!(nfa.run("abbab"));
+
+        assert!(!nfa.run("aa"));
+        assert!(!nfa.run("ba"));
+        assert!(!nfa.run("abc"));
+        assert!(!nfa.run("\0"));
+
+        let nfa = test_utils::prepare_nfa();
+        assert!(nfa.run("B"));
+        assert!(nfa.run("AACCB"));
+        assert!(!nfa.run("AC"));
+    }
+
+    #[test]
+    fn nfa_bracket_kleene_star() {
+        // the kleene star only applies to the bracket before it
+        let nfa = Nfa::from("a(b|c)*").unwrap();
+        assert!(nfa.run("a"));
+        assert!(nfa.run("abcb"));
+        assert!(!nfa.run(""));
+        assert!(!nfa.run("aba"));
+
+        let nfa = Nfa::from("(a)(b)*").unwrap();
+        assert!(nfa.run("abb"));
+        assert!(!nfa.run("aab"));
+    }
+
+    #[test]
+    fn nfa_run_agrees_with_dfa() {
+        let nfa = Nfa::from("a*b*").unwrap();
+        let dfa = nfa.to_dfa();
+
+        for input in ["", "a", "b", "ab", "aab", "abb", "ba", "aba", "bba"] {
+            assert_eq!(dfa.run(input).unwrap(), nfa.run(input), "input: {:?}", input);
+        }
+    }
 }
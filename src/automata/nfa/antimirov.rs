@@ -0,0 +1,153 @@
+use super::Nfa;
+use crate::automata::iters::*;
+use crate::automata::traits::*;
+use crate::language::Regex;
+use std::collections::{HashMap, VecDeque};
+
+impl Nfa {
+    /// Builds the partial derivative automaton of a regex
+    /// parsed by `Regex::parse`.
+    ///
+    /// The nfa has no transitions on the empty string and every
+    /// state is a partial derivative of the regex, which makes it
+    /// at most as large as the position automaton of `Nfa::glushkov`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::antimirov("a|(ab|b)*").unwrap();
+    ///
+    /// assert_eq!(4, nfa.num_states());
+    /// assert!(nfa.run("abbab"));
+    /// assert!(!nfa.run("aa"));
+    /// ```
+    pub fn antimirov(expression: &str) -> Result<Self, &'static str> {
+        Ok(Nfa::antimirov_from_regex(&Regex::parse(expression)?)?.0)
+    }
+
+    /// Builds the partial derivative automaton of the given syntax tree,
+    /// and returns it along with the regex of every state
+    pub fn antimirov_from_regex(regex: &Regex) -> Result<(Self, Vec<Regex>), &'static str> {
+        let alphabet = regex.alphabet()?;
+        let mut states: Vec<Regex> = vec![regex.normalized()];
+        let mut lookup_table: HashMap<Regex, u32> = HashMap::from([(states[0].clone(), 0)]);
+        let mut queue: VecDeque<u32> = VecDeque::from([0]);
+        let mut transitions: Vec<((u32, char), u32)> = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            for &symbol in alphabet.iter() {
+                for derivative in states[current as usize].partial_derivatives(symbol) {
+                    let target = match lookup_table.get(&derivative) {
+                        Some(&target) => target,
+                        None => {
+                            let target = states.len() as u32;
+                            lookup_table.insert(derivative.clone(), target);
+                            states.push(derivative);
+                            queue.push_back(target);
+                            target
+                        }
+                    };
+
+                    transitions.push(((current, symbol), target));
+                }
+            }
+        }
+
+        let mut nfa = Nfa::new();
+        (1..states.len()).for_each(|_| nfa.add_state());
+        alphabet
+            .into_iter()
+            .for_each(|symbol| nfa.add_symbol(symbol));
+
+        for ((source, symbol), target) in transitions {
+            nfa.add_transition(&(source, Some(symbol)), target)?;
+        }
+        for (state, regex) in states.iter().enumerate() {
+            if regex.nullable() {
+                nfa.add_accept_state(state as u32);
+            }
+        }
+
+        Ok((nfa, states))
+    }
+
+    /// Builds both the position and the partial derivative automaton
+    /// of a regex parsed by `Regex::parse` and returns the smaller one,
+    /// the one with fewer states, or fewer transitions if they have
+    /// the same number of states.
+    pub fn smallest(expression: &str) -> Result<Self, &'static str> {
+        let regex = Regex::parse(expression)?;
+        let glushkov = Nfa::glushkov_from_regex(&regex)?;
+        let (antimirov, _) = Nfa::antimirov_from_regex(&regex)?;
+
+        let size = |nfa: &Nfa| {
+            let transitions: usize = nfa
+                .transitions_iter()
+                .map(|(_, targets)| targets.len())
+                .sum();
+            (nfa.num_states(), transitions)
+        };
+
+        if size(&antimirov) <= size(&glushkov) {
+            Ok(antimirov)
+        } else {
+            Ok(glushkov)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn antimirov_construction() {
+        let regex = Regex::parse("a|(ab|b)*").unwrap();
+        let (nfa, labels) = Nfa::antimirov_from_regex(&regex).unwrap();
+
+        let labels: Vec<String> = labels.iter().map(|label| label.to_string()).collect();
+        assert_eq!(vec!["a|(b|ab)*", "()", "b(b|ab)*", "(b|ab)*"], labels);
+
+        assert_eq!(vec![0, 1, 3], nfa.accept_states);
+        assert_eq!(Some(&vec![1, 2]), nfa.get_transition((0, Some('a'))));
+        assert_eq!(Some(&vec![3]), nfa.get_transition((0, Some('b'))));
+        assert_eq!(None, nfa.get_transition((1, Some('a'))));
+        assert_eq!(Some(&vec![3]), nfa.get_transition((2, Some('b'))));
+        assert_eq!(Some(&vec![2]), nfa.get_transition((3, Some('a'))));
+        assert_eq!(Some(&vec![3]), nfa.get_transition((3, Some('b'))));
+    }
+
+    #[test]
+    fn antimirov_agrees_with_thompson() {
+        let inputs = [
+            "", "a", "b", "ab", "ba", "abb", "abab", "aab", "bbbab", "c", "abc",
+        ];
+
+        for expression in ["a|(ab|b)*", "(ab)*", "a*b*", "(a|b)*abb", "((a|())b)*c"] {
+            let thompson = Nfa::from(expression).unwrap();
+            let antimirov = Nfa::antimirov(expression).unwrap();
+            let glushkov = Nfa::glushkov(expression).unwrap();
+
+            assert!(antimirov.num_states() <= glushkov.num_states());
+            for input in inputs {
+                assert_eq!(
+                    thompson.run(input),
+                    antimirov.run(input),
+                    "{} {:?}",
+                    expression,
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn smallest_construction() {
+        // the partial derivatives of (a|b)*a(a|b) are smaller than the positions
+        let nfa = Nfa::smallest("(a|b)*a(a|b)").unwrap();
+        assert_eq!(3, nfa.num_states());
+        assert!(nfa.run("bab"));
+        assert!(!nfa.run("bba"));
+    }
+}
@@ -0,0 +1,117 @@
+use super::Nfa;
+use crate::automata::att::{self, Att};
+use crate::automata::traits::*;
+
+impl Nfa {
+    /// Reads an nfa from an acceptor in the AT&T text format of OpenFst.
+    /// Like in OpenFst, the labels are symbol names if a symbol table is
+    /// given, and numbers otherwise, which are read as code points.
+    /// `<eps>` and 0 labels become transitions on the empty string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::from_att("0 1 97\n1 0 0\n1\n", None).unwrap();
+    ///
+    /// assert!(nfa.run("aaa"));
+    /// assert!(!nfa.run(""));
+    /// ```
+    pub fn from_att(input: &str, symbol_table: Option<&str>) -> Result<Self, &'static str> {
+        let att = Att::parse(input, symbol_table)?;
+
+        let mut nfa = Nfa::new();
+        (1..att.num_states).for_each(|_| nfa.add_state());
+        att.alphabet()
+            .into_iter()
+            .for_each(|symbol| nfa.add_symbol(symbol));
+        att.accept_states
+            .iter()
+            .for_each(|&state| nfa.add_accept_state(state));
+
+        for &(source, symbol, target) in att.transitions.iter() {
+            nfa.add_transition(&(source, symbol), target)?;
+        }
+
+        Ok(nfa)
+    }
+
+    /// Returns the nfa in the AT&T text format, with the code points
+    /// of the symbols as labels and 0 for the empty string
+    pub fn to_att(&self) -> String {
+        self.att().to_text(None).unwrap()
+    }
+
+    /// Returns the nfa in the AT&T text format, with symbol names
+    /// from the given symbol table as labels, which OpenFst reads
+    /// with `fstcompile --acceptor --isymbols`
+    pub fn to_att_with(&self, symbol_table: &str) -> Result<String, &'static str> {
+        self.att().to_text(Some(symbol_table))
+    }
+
+    fn att(&self) -> Att {
+        let first = self.start_state();
+
+        Att {
+            num_states: self.num_states() as u32,
+            accept_states: self
+                .accept_states
+                .iter()
+                .map(|state| state - first)
+                .collect(),
+            transitions: self
+                .transition_fn
+                .iter()
+                .flat_map(|((source, symbol), targets)| {
+                    targets
+                        .iter()
+                        .map(move |target| (source - first, *symbol, target - first))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the symbol table of the nfa, with `<eps>` as 0
+    /// and the symbols of the alphabet numbered from 1
+    pub fn att_symbol_table(&self) -> String {
+        att::symbol_table(&self.alphabet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::EMPTY_STRING;
+
+    #[test]
+    fn nfa_att_round_trip() {
+        let nfa = Nfa::from("a|(ab|b)*").unwrap();
+        let read = Nfa::from_att(&nfa.to_att(), None).unwrap();
+
+        assert_eq!(nfa.states, read.states);
+        assert_eq!(nfa.accept_states, read.accept_states);
+        assert_eq!(nfa.transition_fn, read.transition_fn);
+        assert!(nfa.to_att().contains("\t0\n"));
+        assert!(read.transition_fn.contains_key(&(0, EMPTY_STRING)));
+
+        let table = nfa.att_symbol_table();
+        let text = nfa.to_att_with(&table).unwrap();
+        assert!(text.contains("\t<eps>\n"));
+        let read = Nfa::from_att(&text, Some(&table)).unwrap();
+        assert_eq!(nfa.transition_fn, read.transition_fn);
+    }
+
+    #[test]
+    fn nfa_att_unreachable_states() {
+        let mut nfa = Nfa::new();
+        nfa.add_state();
+        nfa.add_symbol('a');
+        nfa.add_accept_state(0);
+        nfa.add_transition(&(1, Some('a')), 0).unwrap();
+
+        let read = Nfa::from_att(&nfa.to_att(), None).unwrap();
+        assert_eq!(1, read.num_states());
+        assert!(read.run(""));
+        assert!(!read.run("a"));
+    }
+}
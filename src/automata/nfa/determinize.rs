@@ -0,0 +1,370 @@
+use super::Nfa;
+use crate::automata::dfa::Dfa;
+use crate::automata::error::BuildError;
+use crate::language::Symbol;
+use std::collections::{HashMap, VecDeque};
+use std::mem::size_of;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Limits for the subset construction, `None` means there is no limit.
+///
+/// # Example
+/// ```rust
+/// use fmsi::nfa::{BuildError, DeterminizeOptions, Nfa};
+///
+/// let nfa = Nfa::from(&format!("(a|b)*a{}", "(a|b)".repeat(20))).unwrap();
+/// let options = DeterminizeOptions {
+///     max_states: Some(10_000),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     Some(BuildError::TooManyStates { max_states: 10_000 }),
+///     nfa.to_dfa_with(&options).err()
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeterminizeOptions {
+    /// Greatest number of states the dfa can have
+    pub max_states: Option<usize>,
+    /// Roughly the greatest number of bytes the construction can use.
+    /// It counts the memory taken by the sets of nfa states behind the
+    /// dfa states, the table for looking them up, the transitions and
+    /// the queue of states to visit, by the capacity of each of them.
+    pub max_memory: Option<usize>,
+}
+
+/// A set of nfa states stored as a bitset,
+/// where the bit at an index marks the state at that index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct StateSet {
+    words: Box<[u64]>,
+}
+
+impl StateSet {
+    /// Creates an empty set that can hold `len` states
+    pub(crate) fn new(len: usize) -> Self {
+        StateSet {
+            words: vec![0; len.div_ceil(WORD_BITS)].into_boxed_slice(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn insert(&mut self, index: usize) {
+        self.words[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+    }
+
+    #[inline]
+    pub(crate) fn contains(&self, index: usize) -> bool {
+        self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+    }
+
+    pub(crate) fn union_with(&mut self, other: &StateSet) {
+        self.words
+            .iter_mut()
+            .zip(other.words.iter())
+            .for_each(|(word, other)| *word |= other);
+    }
+
+    /// Returns the number of bytes the set takes up on the heap
+    pub(crate) fn heap_size(&self) -> usize {
+        size_of::<u64>() * self.words.len()
+    }
+
+    /// Returns the indexes of the states in the set, in order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(index * WORD_BITS + bit)
+            })
+        })
+    }
+}
+
+/// Runs the subset construction on an nfa.
+///
+/// Nfa states are referred to by their index in the nfa, which lets
+/// every set of states be a bitset that's found through hashing.
+/// Epsilon clojures of every state and the targets of every state
+/// for every class of symbols are computed up front.
+pub(crate) struct Determinizer<'n, S> {
+    nfa: &'n Nfa<S>,
+    classes: Vec<Vec<S>>,
+    /// Epsilon clojure of every state
+    clojures: Vec<StateSet>,
+    /// Indexes of the states every state transitions to for every class
+    targets: Vec<Vec<Vec<usize>>>,
+    accepting: Vec<bool>,
+}
+
+impl<'n, S: Symbol> Determinizer<'n, S> {
+    pub(crate) fn new(nfa: &'n Nfa<S>) -> Self {
+        let first = nfa.start_state();
+        let len = nfa.num_states();
+        let classes = nfa.symbol_classes();
+
+        let mut epsilons: Vec<Vec<usize>> = vec![Vec::new(); len];
+        let mut targets: Vec<Vec<Vec<usize>>> = vec![vec![Vec::new(); classes.len()]; len];
+        let class_of: HashMap<&S, usize> = classes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, class)| class.iter().map(move |symbol| (symbol, index)))
+            .collect();
+
+        for ((source, symbol), destinations) in nfa.transition_fn.iter() {
+            let source = (source - first) as usize;
+            let destinations = destinations.iter().map(|&state| (state - first) as usize);
+
+            match symbol {
+                None => epsilons[source].extend(destinations),
+                // every symbol in the class has the same targets,
+                // so they only have to be stored once
+                Some(symbol) => {
+                    let targets = &mut targets[source][class_of[symbol]];
+                    if targets.is_empty() {
+                        targets.extend(destinations);
+                    }
+                }
+            }
+        }
+
+        let clojures = (0..len)
+            .map(|state| {
+                let mut clojure = StateSet::new(len);
+                let mut stack = vec![state];
+                clojure.insert(state);
+
+                while let Some(current) = stack.pop() {
+                    for &next in epsilons[current].iter() {
+                        if !clojure.contains(next) {
+                            clojure.insert(next);
+                            stack.push(next);
+                        }
+                    }
+                }
+
+                clojure
+            })
+            .collect();
+
+        let accepting = (0..len)
+            .map(|state| nfa.is_accept_state(state as u32 + first))
+            .collect();
+
+        Determinizer {
+            nfa,
+            classes,
+            clojures,
+            targets,
+            accepting,
+        }
+    }
+
+    /// Returns the epsilon clojure of the start state
+    pub(crate) fn start_set(&self) -> StateSet {
+        self.clojures[0].clone()
+    }
+
+    /// Returns the epsilon clojure of the set
+    /// the given set transitions to for the given class
+    pub(crate) fn step(&self, set: &StateSet, class: usize) -> StateSet {
+        let mut next = StateSet::new(self.clojures.len());
+
+        for state in set.iter() {
+            for &target in self.targets[state][class].iter() {
+                next.union_with(&self.clojures[target]);
+            }
+        }
+
+        next
+    }
+
+    pub(crate) fn is_accepting(&self, set: &StateSet) -> bool {
+        set.iter().any(|state| self.accepting[state])
+    }
+
+    /// Returns the nfa states in the given set
+    pub(crate) fn states(&self, set: &StateSet) -> Vec<u32> {
+        let first = self.nfa.start_state();
+        set.iter().map(|index| index as u32 + first).collect()
+    }
+
+    /// Builds the complete dfa along with the set of nfa states
+    /// behind every dfa state, or returns an error
+    /// if it goes over one of the limits
+    pub(crate) fn run(
+        &self,
+        options: &DeterminizeOptions,
+    ) -> Result<(Dfa<S>, Vec<StateSet>), BuildError> {
+        let mut sets: Vec<StateSet> = vec![self.start_set()];
+        let mut lookup_table: HashMap<StateSet, u32> = HashMap::new();
+        let mut queue: VecDeque<u32> = VecDeque::new();
+        let mut transition_fn: HashMap<(u32, S), u32> = HashMap::new();
+
+        lookup_table.insert(sets[0].clone(), 0);
+        queue.push_back(0);
+
+        // every set is stored in the list of sets and the lookup table,
+        // and the hash tables take up an extra byte for every entry
+        let set_size = self.start_set().heap_size();
+        let check_memory = |sets: &Vec<StateSet>,
+                            lookup_table: &HashMap<StateSet, u32>,
+                            queue: &VecDeque<u32>,
+                            transition_fn: &HashMap<(u32, S), u32>| {
+            let memory_usage = sets.capacity() * size_of::<StateSet>()
+                + lookup_table.capacity() * (size_of::<(StateSet, u32)>() + 1)
+                + 2 * sets.len() * set_size
+                + queue.capacity() * size_of::<u32>()
+                + transition_fn.capacity() * (size_of::<((u32, S), u32)>() + 1);
+
+            match options.max_memory {
+                Some(max_memory) if memory_usage > max_memory => {
+                    Err(BuildError::TooMuchMemory { max_memory })
+                }
+                _ => Ok(()),
+            }
+        };
+        check_memory(&sets, &lookup_table, &queue, &transition_fn)?;
+
+        while let Some(current) = queue.pop_front() {
+            for (index, class) in self.classes.iter().enumerate() {
+                let next = self.step(&sets[current as usize], index);
+
+                let target = match lookup_table.get(&next) {
+                    Some(&target) => target,
+                    None => {
+                        if let Some(max_states) = options.max_states {
+                            if sets.len() >= max_states {
+                                return Err(BuildError::TooManyStates { max_states });
+                            }
+                        }
+
+                        let target = sets.len() as u32;
+                        lookup_table.insert(next.clone(), target);
+                        sets.push(next);
+                        queue.push_back(target);
+                        check_memory(&sets, &lookup_table, &queue, &transition_fn)?;
+                        target
+                    }
+                };
+
+                for symbol in class.iter() {
+                    transition_fn.insert((current, symbol.clone()), target);
+                }
+            }
+            check_memory(&sets, &lookup_table, &queue, &transition_fn)?;
+        }
+
+        let accept_states = (0..sets.len() as u32)
+            .filter(|&state| self.is_accepting(&sets[state as usize]))
+            .collect();
+
+        let dfa = Dfa::from_parts(
+            (0..sets.len() as u32).collect(),
+            self.nfa.alphabet.clone(),
+            transition_fn,
+            accept_states,
+        );
+
+        Ok((dfa, sets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_set() {
+        let mut set = StateSet::new(130);
+        assert_eq!(0, set.iter().count());
+
+        for index in [129, 0, 64, 63] {
+            set.insert(index);
+        }
+
+        assert!(set.contains(64));
+        assert!(!set.contains(65));
+        assert_eq!(vec![0, 63, 64, 129], set.iter().collect::<Vec<usize>>());
+
+        let mut other = StateSet::new(130);
+        other.insert(1);
+        other.union_with(&set);
+        assert_eq!(vec![0, 1, 63, 64, 129], other.iter().collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn determinizer_budget() {
+        let expression = format!("(a|b)*a{}", "(a|b)".repeat(4));
+        let nfa = Nfa::from(&expression).unwrap();
+
+        let determinizer = Determinizer::new(&nfa);
+        let (mut dfa, _) = determinizer.run(&DeterminizeOptions::default()).unwrap();
+        let num_states = dfa.num_states();
+
+        let options = DeterminizeOptions {
+            max_states: Some(num_states - 1),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(BuildError::TooManyStates {
+                max_states: num_states - 1
+            }),
+            determinizer.run(&options).map(|(dfa, _)| dfa.num_states())
+        );
+
+        let options = DeterminizeOptions {
+            max_states: Some(num_states),
+            ..Default::default()
+        };
+        assert!(determinizer.run(&options).is_ok());
+
+        // the minimal dfa remembers the last 5 symbols
+        dfa.minimize().unwrap();
+        assert_eq!(32, dfa.num_states());
+    }
+
+    #[test]
+    fn determinizer_counts_start_set() {
+        let nfa = Nfa::from("a").unwrap();
+        let options = DeterminizeOptions {
+            max_memory: Some(0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Err(BuildError::TooMuchMemory { max_memory: 0 }),
+            nfa.to_dfa_with(&options).map(|dfa| dfa.num_states())
+        );
+    }
+
+    #[test]
+    fn determinizer_memory_limit() {
+        let expression = format!("(a|b)*a{}", "(a|b)".repeat(12));
+        let nfa = Nfa::from(&expression).unwrap();
+        let options = DeterminizeOptions {
+            max_memory: Some(64 * 1024),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Err(BuildError::TooMuchMemory {
+                max_memory: 64 * 1024
+            }),
+            nfa.to_dfa_with(&options).map(|dfa| dfa.num_states())
+        );
+
+        let options = DeterminizeOptions {
+            max_memory: Some(16 * 1024 * 1024),
+            ..Default::default()
+        };
+        assert!(nfa.to_dfa_with(&options).is_ok());
+    }
+}
@@ -0,0 +1,210 @@
+use super::Nfa;
+use crate::automata::traits::*;
+use crate::language::Regex;
+use std::collections::BTreeSet;
+
+/// Nullability, first and last positions of a sub-expression
+struct Positions {
+    nullable: bool,
+    first: BTreeSet<u32>,
+    last: BTreeSet<u32>,
+}
+
+/// Numbers every symbol occurrence in the regex, starting from 1,
+/// and fills in the follow sets of the positions.
+struct Linearizer {
+    /// Chars matched by every position, position 0 is the start state
+    chars: Vec<Vec<char>>,
+    follow: Vec<BTreeSet<u32>>,
+}
+
+impl Linearizer {
+    fn positions(&mut self, regex: &Regex) -> Result<Positions, &'static str> {
+        match regex {
+            Regex::EmptySet => Ok(Positions {
+                nullable: false,
+                first: BTreeSet::new(),
+                last: BTreeSet::new(),
+            }),
+            Regex::EmptyString => Ok(Positions {
+                nullable: true,
+                first: BTreeSet::new(),
+                last: BTreeSet::new(),
+            }),
+            Regex::Symbol(_) | Regex::Class(_) => {
+                let position = self.chars.len() as u32;
+                self.chars.push(regex.chars()?);
+                self.follow.push(BTreeSet::new());
+
+                Ok(Positions {
+                    nullable: false,
+                    first: BTreeSet::from([position]),
+                    last: BTreeSet::from([position]),
+                })
+            }
+            Regex::Concat(first, second) => {
+                let mut first = self.positions(first)?;
+                let mut second = self.positions(second)?;
+
+                for &position in first.last.iter() {
+                    self.follow[position as usize].extend(second.first.iter());
+                }
+
+                if first.nullable {
+                    first.first.extend(second.first.iter());
+                }
+                if second.nullable {
+                    second.last.extend(first.last.iter());
+                }
+
+                Ok(Positions {
+                    nullable: first.nullable && second.nullable,
+                    first: first.first,
+                    last: second.last,
+                })
+            }
+            Regex::Union(first, second) => {
+                let mut first = self.positions(first)?;
+                let second = self.positions(second)?;
+
+                first.first.extend(second.first);
+                first.last.extend(second.last);
+                first.nullable |= second.nullable;
+                Ok(first)
+            }
+            Regex::KleeneStar(inner) => {
+                let mut inner = self.positions(inner)?;
+
+                for &position in inner.last.iter() {
+                    self.follow[position as usize].extend(inner.first.iter());
+                }
+
+                inner.nullable = true;
+                Ok(inner)
+            }
+        }
+    }
+}
+
+impl Nfa {
+    /// Builds the position automaton of a regex parsed by `Regex::parse`.
+    ///
+    /// The nfa has no transitions on the empty string and it has
+    /// a state for every occurrence of a symbol or a class in the
+    /// regex, plus the start state 0. Classes are expanded in to
+    /// their chars, so they can't be too large.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::glushkov("a|(ab|b)*").unwrap();
+    ///
+    /// assert_eq!(5, nfa.num_states());
+    /// assert!(nfa.run("abbab"));
+    /// assert!(!nfa.run("aa"));
+    /// ```
+    pub fn glushkov(expression: &str) -> Result<Self, &'static str> {
+        Nfa::glushkov_from_regex(&Regex::parse(expression)?)
+    }
+
+    /// Builds the position automaton of the given syntax tree
+    pub fn glushkov_from_regex(regex: &Regex) -> Result<Self, &'static str> {
+        let mut linearizer = Linearizer {
+            chars: vec![Vec::new()],
+            follow: vec![BTreeSet::new()],
+        };
+        let positions = linearizer.positions(regex)?;
+
+        // the start state is followed by the first positions
+        linearizer.follow[0] = positions.first;
+
+        let mut nfa = Nfa::new();
+        (1..linearizer.chars.len()).for_each(|_| nfa.add_state());
+        regex
+            .alphabet()?
+            .into_iter()
+            .for_each(|symbol| nfa.add_symbol(symbol));
+
+        if positions.nullable {
+            nfa.add_accept_state(0);
+        }
+        positions
+            .last
+            .iter()
+            .for_each(|&position| nfa.add_accept_state(position));
+
+        // every transition in to a position is on one of it's chars
+        for (source, targets) in linearizer.follow.iter().enumerate() {
+            for &target in targets.iter() {
+                for &symbol in linearizer.chars[target as usize].iter() {
+                    nfa.add_transition(&(source as u32, Some(symbol)), target)?;
+                }
+            }
+        }
+
+        Ok(nfa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::iters::*;
+
+    #[test]
+    fn glushkov_construction() {
+        // positions: a1 | (a2 b3 | b4)*
+        let nfa = Nfa::glushkov("a|(ab|b)*").unwrap();
+
+        assert_eq!(vec![0, 1, 2, 3, 4], nfa.states);
+        assert_eq!(vec!['a', 'b'], nfa.alphabet);
+        assert_eq!(vec![0, 1, 3, 4], nfa.accept_states);
+        assert_eq!(Some(&vec![1, 2]), nfa.get_transition((0, Some('a'))));
+        assert_eq!(Some(&vec![4]), nfa.get_transition((0, Some('b'))));
+        assert_eq!(None, nfa.get_transition((1, Some('a'))));
+        assert_eq!(None, nfa.get_transition((1, Some('b'))));
+        assert_eq!(Some(&vec![3]), nfa.get_transition((2, Some('b'))));
+        assert_eq!(Some(&vec![2]), nfa.get_transition((3, Some('a'))));
+        assert_eq!(Some(&vec![4]), nfa.get_transition((3, Some('b'))));
+        assert_eq!(Some(&vec![2]), nfa.get_transition((4, Some('a'))));
+        assert_eq!(Some(&vec![4]), nfa.get_transition((4, Some('b'))));
+        assert!(nfa
+            .transitions_iter()
+            .all(|((_, symbol), _)| symbol.is_some()));
+    }
+
+    #[test]
+    fn glushkov_agrees_with_thompson() {
+        let inputs = [
+            "", "a", "b", "ab", "ba", "abb", "abab", "aab", "bbbab", "c", "abc",
+        ];
+
+        for expression in ["a|(ab|b)*", "(ab)*", "a*b*", "(a|b)*abb", "((a|())b)*c"] {
+            let thompson = Nfa::from(expression).unwrap();
+            let glushkov = Nfa::glushkov(expression).unwrap();
+
+            for input in inputs {
+                assert_eq!(
+                    thompson.run(input),
+                    glushkov.run(input),
+                    "{} {:?}",
+                    expression,
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn glushkov_classes() {
+        let nfa = Nfa::glushkov("[a-c]x*").unwrap();
+
+        assert_eq!(3, nfa.num_states());
+        assert!(nfa.run("b"));
+        assert!(nfa.run("cxx"));
+        assert!(!nfa.run("x"));
+
+        assert!(Nfa::glushkov(".").is_err());
+    }
+}
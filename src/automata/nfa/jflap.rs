@@ -0,0 +1,101 @@
+use super::Nfa;
+use crate::automata::jflap::{Coordinates, Jflap};
+use crate::automata::traits::*;
+
+impl Nfa {
+    /// Reads an nfa from a JFLAP file, and returns it along with
+    /// the positions of it's states. Lambda transitions become
+    /// transitions on the empty string.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::Nfa;
+    ///
+    /// let nfa = Nfa::from("a|b*").unwrap();
+    /// let (read, coordinates) = Nfa::from_jflap(&nfa.to_jflap()).unwrap();
+    ///
+    /// assert_eq!(nfa.num_states(), read.num_states());
+    /// assert_eq!(Some(&(100.0, 100.0)), coordinates.get(&0));
+    /// assert!(read.run("bbb"));
+    /// ```
+    pub fn from_jflap(input: &str) -> Result<(Self, Coordinates), &'static str> {
+        let jflap = Jflap::parse(input)?;
+
+        let mut nfa = Nfa::new();
+        (1..jflap.num_states).for_each(|_| nfa.add_state());
+        jflap
+            .alphabet()
+            .into_iter()
+            .for_each(|symbol| nfa.add_symbol(symbol));
+        jflap
+            .accept_states
+            .iter()
+            .for_each(|&state| nfa.add_accept_state(state));
+
+        for &(source, symbol, target) in jflap.transitions.iter() {
+            nfa.add_transition(&(source, symbol), target)?;
+        }
+
+        Ok((nfa, jflap.coordinates))
+    }
+
+    /// Returns the nfa as a JFLAP file, with the states placed on a line
+    pub fn to_jflap(&self) -> String {
+        self.to_jflap_with(&Coordinates::new())
+    }
+
+    /// Returns the nfa as a JFLAP file, with the states
+    /// at the given positions
+    pub fn to_jflap_with(&self, coordinates: &Coordinates) -> String {
+        let first = self.start_state();
+
+        let transitions = self
+            .transition_fn
+            .iter()
+            .flat_map(|((source, symbol), targets)| {
+                targets
+                    .iter()
+                    .map(move |target| (source - first, *symbol, target - first))
+            })
+            .collect();
+
+        let jflap = Jflap {
+            num_states: self.num_states() as u32,
+            accept_states: self
+                .accept_states
+                .iter()
+                .map(|state| state - first)
+                .collect(),
+            transitions,
+            coordinates: coordinates
+                .iter()
+                .filter(|(state, _)| self.states.contains(state))
+                .map(|(state, &position)| (state - first, position))
+                .collect(),
+        };
+
+        jflap.to_xml()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::iters::*;
+
+    #[test]
+    fn nfa_jflap_round_trip() {
+        let nfa = Nfa::from("a|(ab|b)*").unwrap();
+        let coordinates = Coordinates::from([(0, (12.5, 40.0)), (3, (200.0, -7.25))]);
+
+        let (read, read_coordinates) = Nfa::from_jflap(&nfa.to_jflap_with(&coordinates)).unwrap();
+
+        assert_eq!(nfa.states, read.states);
+        assert_eq!(nfa.accept_states, read.accept_states);
+        assert_eq!(nfa.transition_fn, read.transition_fn);
+        assert!(read.get_transition((0, None)).is_some());
+        assert_eq!(Some(&(12.5, 40.0)), read_coordinates.get(&0));
+        assert_eq!(Some(&(200.0, -7.25)), read_coordinates.get(&3));
+        assert_eq!(nfa.num_states(), read_coordinates.len());
+    }
+}
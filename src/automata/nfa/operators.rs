@@ -1,6 +1,6 @@
 use super::Nfa;
 use crate::automata::traits::*;
-use crate::language::EMPTY_STRING;
+use crate::language::Symbol;
 
 /// Concatenates the second Nfa on to the first
 /// and ads an epsilon transition in between.
@@ -8,11 +8,11 @@ use crate::language::EMPTY_STRING;
 /// function consumes the second Nfa.
 ///
 /// The function assumes that the alphabets are identical.
-pub fn concat(first: &mut Nfa, mut second: Nfa) -> Result<(), &'static str> {
+pub fn concat<S: Symbol>(first: &mut Nfa<S>, mut second: Nfa<S>) -> Result<(), &'static str> {
     // copy the missing symbols in the alphabet
     for symbol in second.alphabet.iter() {
         if !first.alphabet.contains(symbol) {
-            first.alphabet.push(*symbol);
+            first.alphabet.push(symbol.clone());
         }
     }
 
@@ -28,16 +28,16 @@ pub fn concat(first: &mut Nfa, mut second: Nfa) -> Result<(), &'static str> {
     // onto the first
     for entry in second.transition_fn.iter() {
         for state in entry.1 {
-            first.add_transition(&(entry.0 .0, entry.0 .1), *state)?;
+            first.add_transition(entry.0, *state)?;
         }
     }
 
     if first.accept_states.is_empty() {
-        first.add_transition(&(increment - 1, EMPTY_STRING), increment)?
+        first.add_transition(&(increment - 1, None), increment)?
     } else {
         // concect first's accept states with second's start state
         for accept_state in first.accept_states.clone() {
-            first.add_transition(&(accept_state, EMPTY_STRING), increment)?;
+            first.add_transition(&(accept_state, None), increment)?;
         }
     }
 
@@ -53,7 +53,7 @@ pub fn concat(first: &mut Nfa, mut second: Nfa) -> Result<(), &'static str> {
 }
 
 /// Applies the kleene star operator to a given Nfa
-pub fn kleene_star(nfa: &mut Nfa) -> Result<(), &'static str> {
+pub fn kleene_star<S: Symbol>(nfa: &mut Nfa<S>) -> Result<(), &'static str> {
     // add a new final state at the beggining
     nfa.increment_states(1);
     nfa.states.insert(0, 0);
@@ -63,7 +63,7 @@ pub fn kleene_star(nfa: &mut Nfa) -> Result<(), &'static str> {
     // final state to the previous first state
     let accept_states = nfa.accept_states.clone();
     accept_states.iter().for_each(|e| {
-        nfa.add_transition(&(*e, EMPTY_STRING), 1).unwrap();
+        nfa.add_transition(&(*e, None), 1).unwrap();
     });
 
     Ok(())
@@ -72,7 +72,7 @@ pub fn kleene_star(nfa: &mut Nfa) -> Result<(), &'static str> {
 /// Applies the union operator on two given operators.
 /// The union is applied on to the first nfa while
 /// the second gets consumed.
-pub fn union(first: &mut Nfa, mut second: Nfa) -> Result<(), &'static str> {
+pub fn union<S: Symbol>(first: &mut Nfa<S>, mut second: Nfa<S>) -> Result<(), &'static str> {
     // add a new state to the beggining of the first
     first.increment_states(1);
     first.states.insert(0, 0);
@@ -102,14 +102,14 @@ pub fn union(first: &mut Nfa, mut second: Nfa) -> Result<(), &'static str> {
     // ad second's alphabet to first
     for symbol in second.alphabet.iter() {
         if !first.alphabet.contains(symbol) {
-            first.alphabet.push(*symbol);
+            first.alphabet.push(symbol.clone());
         }
     }
 
     // conncect new first transition with the
     // two branches
-    first.add_transition(&(0, EMPTY_STRING), 1)?;
-    first.add_transition(&(0, EMPTY_STRING), other_start_state)?;
+    first.add_transition(&(0, None), 1)?;
+    first.add_transition(&(0, None), other_start_state)?;
 
     Ok(())
 }
@@ -117,6 +117,7 @@ pub fn union(first: &mut Nfa, mut second: Nfa) -> Result<(), &'static str> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::language::EMPTY_STRING;
     use crate::automata::nfa::test_utils;
 
     #[test]
@@ -129,11 +130,11 @@ mod tests {
         first.add_symbol('A');
         first.add_symbol('B');
         first.add_transition(&(0, EMPTY_STRING), 1).unwrap();
-        first.add_transition(&(0, 'A'), 0).unwrap();
-        first.add_transition(&(0, 'B'), 2).unwrap();
+        first.add_transition(&(0, Some('A')), 0).unwrap();
+        first.add_transition(&(0, Some('B')), 2).unwrap();
         first.add_transition(&(1, EMPTY_STRING), 2).unwrap();
-        first.add_transition(&(1, 'A'), 0).unwrap();
-        first.add_transition(&(2, 'A'), 2).unwrap();
+        first.add_transition(&(1, Some('A')), 0).unwrap();
+        first.add_transition(&(2, Some('A')), 2).unwrap();
         first.add_accept_state(2);
         println!("first: {:?}", first.transition_fn);
 
@@ -141,9 +142,9 @@ mod tests {
         second.add_state();
         second.add_symbol('A');
         second.add_symbol('B');
-        second.add_transition(&(0, 'A'), 1).unwrap();
-        second.add_transition(&(1, 'B'), 2).unwrap();
-        second.add_transition(&(2, 'B'), 0).unwrap();
+        second.add_transition(&(0, Some('A')), 1).unwrap();
+        second.add_transition(&(1, Some('B')), 2).unwrap();
+        second.add_transition(&(2, Some('B')), 0).unwrap();
         second.add_transition(&(2, EMPTY_STRING), 1).unwrap();
         second.add_accept_state(2);
         println!("second: {:?}", second.transition_fn);
@@ -156,7 +157,7 @@ mod tests {
             "The number of states is wrong when concatenating"
         );
         assert_eq!(
-            vec![EMPTY_STRING, 'A', 'B'],
+            vec!['A', 'B'],
             first.alphabet,
             "The alphabet symbols don't match"
         );
@@ -165,13 +166,13 @@ mod tests {
             *first.transition_fn.get(&(2, EMPTY_STRING)).unwrap(),
             "An empty string transition is missing between the nfas"
         );
-        assert_eq!(vec![4], *first.transition_fn.get(&(3, 'A')).unwrap());
-        assert_eq!(vec![5], *first.transition_fn.get(&(4, 'B')).unwrap());
+        assert_eq!(vec![4], *first.transition_fn.get(&(3, Some('A'))).unwrap());
+        assert_eq!(vec![5], *first.transition_fn.get(&(4, Some('B'))).unwrap());
         assert_eq!(
             vec![4],
             *first.transition_fn.get(&(5, EMPTY_STRING)).unwrap()
         );
-        assert_eq!(vec![3], *first.transition_fn.get(&(5, 'B')).unwrap());
+        assert_eq!(vec![3], *first.transition_fn.get(&(5, Some('B'))).unwrap());
         println!("result: {:?}", first.transition_fn);
     }
 
@@ -189,8 +190,8 @@ mod tests {
 
         assert_eq!(vec![1], *nfa.transition_fn.get(&(0, EMPTY_STRING)).unwrap());
         assert_eq!(vec![1], *nfa.transition_fn.get(&(4, EMPTY_STRING)).unwrap());
-        assert_eq!(vec![1, 2], *nfa.transition_fn.get(&(1, 'A')).unwrap());
-        assert_eq!(vec![2, 4], *nfa.transition_fn.get(&(3, 'B')).unwrap());
+        assert_eq!(vec![1, 2], *nfa.transition_fn.get(&(1, Some('A'))).unwrap());
+        assert_eq!(vec![2, 4], *nfa.transition_fn.get(&(3, Some('B'))).unwrap());
     }
 
     #[test]
@@ -203,8 +204,8 @@ mod tests {
             vec![1, 5],
             *first.transition_fn.get(&(0, EMPTY_STRING)).unwrap()
         );
-        assert_eq!(vec![4], *first.transition_fn.get(&(3, 'b')).unwrap());
-        assert_eq!(vec![6], *first.transition_fn.get(&(5, 'a')).unwrap());
+        assert_eq!(vec![4], *first.transition_fn.get(&(3, Some('b'))).unwrap());
+        assert_eq!(vec![6], *first.transition_fn.get(&(5, Some('a'))).unwrap());
         assert_eq!(vec![4, 6], first.accept_states);
     }
 }
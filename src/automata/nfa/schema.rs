@@ -0,0 +1,156 @@
+use super::Nfa;
+use crate::automata::traits::*;
+use crate::language::Symbol;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+
+/// The serialized form of an nfa
+#[derive(Serialize, Deserialize)]
+struct NfaSchema<S> {
+    states: Vec<u32>,
+    alphabet: Vec<S>,
+    transitions: Vec<NfaTransition<S>>,
+    start: u32,
+    accepts: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NfaTransition<S> {
+    from: u32,
+    /// `None` for transitions on the empty string
+    symbol: Option<S>,
+    to: u32,
+}
+
+impl<S: Symbol> From<&Nfa<S>> for NfaSchema<S> {
+    fn from(nfa: &Nfa<S>) -> Self {
+        let mut transitions: Vec<(u32, Option<S>, u32)> = nfa
+            .transition_fn
+            .iter()
+            .flat_map(|((from, symbol), targets)| {
+                targets.iter().map(move |&to| (*from, symbol.clone(), to))
+            })
+            .collect();
+        transitions.sort();
+
+        NfaSchema {
+            states: nfa.states.clone(),
+            alphabet: nfa.alphabet.clone(),
+            transitions: transitions
+                .into_iter()
+                .map(|(from, symbol, to)| NfaTransition { from, symbol, to })
+                .collect(),
+            start: nfa.start_state(),
+            accepts: nfa.accept_states.clone(),
+        }
+    }
+}
+
+impl<S: Symbol> TryFrom<NfaSchema<S>> for Nfa<S> {
+    type Error = &'static str;
+
+    fn try_from(schema: NfaSchema<S>) -> Result<Self, Self::Error> {
+        if schema.states.first() != Some(&schema.start) {
+            return Err("The first state must be the start state");
+        }
+        if schema
+            .states
+            .iter()
+            .zip(schema.start..)
+            .any(|(&state, expected)| state != expected)
+        {
+            return Err("The states must be consecutive numbers");
+        }
+
+        let mut nfa = Nfa {
+            states: schema.states,
+            alphabet: Vec::new(),
+            transition_fn: HashMap::new(),
+            accept_states: Vec::new(),
+        };
+
+        for symbol in schema.alphabet {
+            if nfa.alphabet.contains(&symbol) {
+                return Err("The alphabet contains a symbol more than once");
+            }
+            nfa.add_symbol(symbol);
+        }
+
+        for state in schema.accepts {
+            if !nfa.states.contains(&state) {
+                return Err("Accept state is not a valid state");
+            }
+            if !nfa.accept_states.contains(&state) {
+                nfa.add_accept_state(state);
+            }
+        }
+
+        for transition in schema.transitions {
+            nfa.add_transition(&(transition.from, transition.symbol), transition.to)?;
+        }
+
+        Ok(nfa)
+    }
+}
+
+impl<S: Symbol + Serialize> Serialize for Nfa<S> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        NfaSchema::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, S: Symbol + Deserialize<'de>> Deserialize<'de> for Nfa<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Nfa::try_from(NfaSchema::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nfa_serde_round_trip() {
+        let nfa = Nfa::from("a|b*").unwrap();
+
+        let json = serde_json::to_string(&nfa).unwrap();
+        let read: Nfa = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(nfa.states, read.states);
+        assert_eq!(nfa.alphabet, read.alphabet);
+        assert_eq!(nfa.transition_fn, read.transition_fn);
+        assert_eq!(nfa.accept_states, read.accept_states);
+        assert!(json.contains(r#"{"from":0,"symbol":null,"to":1}"#));
+    }
+
+    #[test]
+    fn nfa_serde_validation() {
+        let parse = |json: &str| serde_json::from_str::<Nfa>(json);
+
+        assert!(parse(
+            r#"{"states":[0,1],"alphabet":["a"],"start":0,"accepts":[1],
+                "transitions":[{"from":0,"symbol":"a","to":1},{"from":1,"symbol":null,"to":0}]}"#
+        )
+        .is_ok());
+        // missing state, symbol not in the alphabet, bad accept state, unordered states
+        assert!(parse(
+            r#"{"states":[0,1],"alphabet":["a"],"start":0,"accepts":[1],
+                "transitions":[{"from":0,"symbol":"a","to":2}]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"states":[0,1],"alphabet":["a"],"start":0,"accepts":[1],
+                "transitions":[{"from":0,"symbol":"b","to":1}]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"states":[0,1],"alphabet":["a"],"start":0,"accepts":[5],"transitions":[]}"#
+        )
+        .is_err());
+        assert!(parse(
+            r#"{"states":[1,0],"alphabet":["a"],"start":1,"accepts":[],"transitions":[]}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use super::Nfa;
+use crate::automata::traits::*;
+use crate::language::Symbol;
+use crate::util;
+use std::collections::HashMap;
+
+impl<S: Symbol> Nfa<S> {
+    /// Returns an equivalent nfa without transitions on the empty string.
+    ///
+    /// Every state gets the symbol transitions of all the states in its
+    /// epsilon clojure, and becomes an accept state if its clojure contains
+    /// one. States that become useless are removed afterwards.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::nfa::{Nfa, TransitionIter};
+    ///
+    /// let nfa = Nfa::from("a|(ab|b)*").unwrap();
+    /// let epsilon_free = nfa.remove_epsilons();
+    ///
+    /// assert!(epsilon_free.transitions_iter().all(|((_, symbol), _)| symbol.is_some()));
+    /// assert!(epsilon_free.num_states() < nfa.num_states());
+    /// assert!(epsilon_free.run("abbab"));
+    /// assert!(!epsilon_free.run("aa"));
+    /// ```
+    pub fn remove_epsilons(&self) -> Nfa<S> {
+        let mut nfa = Nfa {
+            states: self.states.clone(),
+            alphabet: self.alphabet.clone(),
+            transition_fn: HashMap::new(),
+            accept_states: Vec::new(),
+        };
+
+        for &state in self.states.iter() {
+            let clojure = util::state_epsilon_clojure(self, state);
+
+            if clojure.iter().any(|&state| self.is_accept_state(state)) {
+                nfa.add_accept_state(state);
+            }
+
+            for symbol in self.alphabet.iter() {
+                let targets = util::set_transitions(self, &clojure, symbol.clone());
+                if !targets.is_empty() {
+                    nfa.transition_fn
+                        .insert((state, Some(symbol.clone())), targets);
+                }
+            }
+        }
+
+        nfa.remove_useless_states()
+    }
+
+    /// Returns an equivalent nfa with only the states that can be reached
+    /// from the start state and that can reach an accept state.
+    /// The start state is always kept, and the states are renamed
+    /// so that they start from 0 and keep their order.
+    pub fn remove_useless_states(&self) -> Nfa<S> {
+        let start_state = self.start_state();
+
+        // states reachable from the start state
+        let mut reachable = vec![start_state];
+        let mut index = 0;
+        while let Some(&current) = reachable.get(index) {
+            for ((source, _), targets) in self.transition_fn.iter() {
+                if *source == current {
+                    for target in targets {
+                        if !reachable.contains(target) {
+                            reachable.push(*target);
+                        }
+                    }
+                }
+            }
+            index += 1;
+        }
+
+        // states that can reach an accept state
+        let mut productive = self.accept_states.clone();
+        let mut done = false;
+        while !done {
+            done = true;
+
+            for ((source, _), targets) in self.transition_fn.iter() {
+                if !productive.contains(source)
+                    && targets.iter().any(|target| productive.contains(target))
+                {
+                    productive.push(*source);
+                    done = false;
+                }
+            }
+        }
+
+        let lookup_table: HashMap<u32, u32> = self
+            .states
+            .iter()
+            .filter(|&&state| {
+                state == start_state || (reachable.contains(&state) && productive.contains(&state))
+            })
+            .enumerate()
+            .map(|(index, &state)| (state, index as u32))
+            .collect();
+
+        let mut nfa = Nfa {
+            states: (0..lookup_table.len() as u32).collect(),
+            alphabet: self.alphabet.clone(),
+            transition_fn: HashMap::new(),
+            accept_states: Vec::new(),
+        };
+
+        for ((source, symbol), targets) in self.transition_fn.iter() {
+            let Some(&source) = lookup_table.get(source) else {
+                continue;
+            };
+
+            let mut targets: Vec<u32> = targets
+                .iter()
+                .filter_map(|target| lookup_table.get(target).copied())
+                .collect();
+            targets.sort();
+
+            if !targets.is_empty() {
+                nfa.transition_fn.insert((source, symbol.clone()), targets);
+            }
+        }
+
+        for state in self.accept_states.iter() {
+            if let Some(&state) = lookup_table.get(state) {
+                nfa.add_accept_state(state);
+            }
+        }
+
+        nfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automata::iters::*;
+
+    #[test]
+    fn nfa_remove_epsilons() {
+        let inputs = ["", "a", "b", "ab", "ba", "abb", "abab", "aab", "bbbab", "c"];
+
+        for expression in ["a|(ab|b)*", "(ab)*", "a*b*", "(a|b)*abb", "((a|())b)*"] {
+            let nfa = Nfa::from(expression).unwrap();
+            let epsilon_free = nfa.remove_epsilons();
+
+            assert!(epsilon_free.get_transition((0, None)).is_none());
+            assert!(epsilon_free
+                .transition_fn
+                .keys()
+                .all(|(_, symbol)| symbol.is_some()));
+
+            for input in inputs {
+                assert_eq!(
+                    nfa.run(input),
+                    epsilon_free.run(input),
+                    "{} {:?}",
+                    expression,
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nfa_remove_useless_states() {
+        let mut nfa: Nfa = Nfa::new();
+        (0..4).for_each(|_| nfa.add_state());
+        nfa.add_symbol('a');
+        nfa.add_symbol('b');
+        nfa.add_accept_state(2);
+
+        // 1 can't reach an accept state and 3 can't be reached
+        nfa.add_transition(&(0, Some('a')), 1).unwrap();
+        nfa.add_transition(&(0, Some('b')), 2).unwrap();
+        nfa.add_transition(&(3, Some('a')), 2).unwrap();
+        nfa.add_transition(&(2, Some('a')), 4).unwrap();
+        nfa.add_transition(&(4, Some('b')), 2).unwrap();
+
+        let trimmed = nfa.remove_useless_states();
+
+        assert_eq!(vec![0, 1, 2], trimmed.states);
+        assert_eq!(vec![1], trimmed.accept_states);
+        assert_eq!(Some(&vec![1]), trimmed.get_transition((0, Some('b'))));
+        assert_eq!(Some(&vec![2]), trimmed.get_transition((1, Some('a'))));
+        assert_eq!(Some(&vec![1]), trimmed.get_transition((2, Some('b'))));
+        assert_eq!(None, trimmed.get_transition((0, Some('a'))));
+        assert!(trimmed.run("bab"));
+        assert!(!trimmed.run("a"));
+    }
+
+    #[test]
+    fn nfa_remove_useless_states_empty_language() {
+        let mut nfa: Nfa = Nfa::new();
+        nfa.add_state();
+        nfa.add_symbol('a');
+        nfa.add_transition(&(0, Some('a')), 1).unwrap();
+
+        let trimmed = nfa.remove_useless_states();
+
+        assert_eq!(vec![0], trimmed.states);
+        assert!(trimmed.transition_fn.is_empty());
+        assert!(!trimmed.run(""));
+        assert!(!trimmed.run("a"));
+    }
+}
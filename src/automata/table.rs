@@ -0,0 +1,297 @@
+//! The plain text transition table format written by the `Display`
+//! implementations of `Nfa` and `Dfa`, and read by their `from_table`.
+//!
+//! The first row is a header with an empty first cell and a symbol in
+//! every other column, `ε` being the column of transitions on the
+//! empty string. The symbols `ε` and `|` and whitespace are written
+//! as `\u{3b5}`, `\u{7c}` and so on. Every other row starts with a
+//! state, marked with `→` or `->` if it's the start state and `*` if
+//! it's an accept state, followed by the targets for every symbol: a
+//! state, a set of states like `{q1, q2}`, or `∅` or `-` for no
+//! target. States can have any names without whitespace, the start
+//! state becomes state 0 and the others are numbered in the order of
+//! their rows. Cells are separated by `|`, and empty lines are
+//! skipped, as are lines of only `-`, `+` and `|` after the header.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// The cell of a symbol a state has no transitions for
+pub(crate) const EMPTY_CELL: &str = "∅";
+
+/// Returns the first cell of a row, marking the start state
+/// with `→` and accept states with `*`
+pub(crate) fn row_label(state: u32, is_start: bool, is_accept: bool) -> String {
+    format!(
+        "{}{} {}",
+        if is_start { "→" } else { " " },
+        if is_accept { "*" } else { " " },
+        state
+    )
+}
+
+/// Returns a symbol as a cell of the header, escaping the
+/// chars that can't be read back as the symbol
+pub(crate) fn symbol_cell(symbol: &str) -> String {
+    symbol
+        .chars()
+        .map(|c| match c {
+            c if matches!(c, 'ε' | '|') || c.is_whitespace() => format!("\\u{{{:x}}}", c as u32),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn parse_symbol_cell(cell: &str) -> Result<Option<char>, &'static str> {
+    let mut chars = cell.chars();
+    match (chars.next(), chars.next()) {
+        (Some('ε'), None) => return Ok(None),
+        (Some(symbol), None) => return Ok(Some(symbol)),
+        _ => {}
+    }
+
+    cell.strip_prefix("\\u{")
+        .and_then(|cell| cell.strip_suffix('}'))
+        .and_then(|code| u32::from_str_radix(code, 16).ok())
+        .and_then(char::from_u32)
+        .map(Some)
+        .ok_or("Every column of the table must have a single symbol")
+}
+
+/// Returns a set of states as a cell of the table
+pub(crate) fn set_cell(states: &[u32]) -> String {
+    if states.is_empty() {
+        return EMPTY_CELL.to_string();
+    }
+
+    let mut states = states.to_vec();
+    states.sort();
+    states.dedup();
+
+    let states: Vec<String> = states.iter().map(|state| state.to_string()).collect();
+    format!("{{{}}}", states.join(", "))
+}
+
+/// Writes the rows with their cells aligned in to columns
+/// separated by `|`, the first row being the header
+pub(crate) fn write_table(f: &mut fmt::Formatter, rows: &[Vec<String>]) -> fmt::Result {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for row in rows {
+        let mut line = String::new();
+        for (column, cell) in row.iter().enumerate() {
+            if column > 0 {
+                line.push_str(" | ");
+            }
+            line.push_str(cell);
+            (cell.chars().count()..widths[column]).for_each(|_| line.push(' '));
+        }
+        writeln!(f, "{}", line.trim_end())?;
+    }
+
+    Ok(())
+}
+
+/// An automata read from the table format, with the
+/// states numbered from 0 and state 0 as the start state
+#[derive(Debug, Default)]
+pub(crate) struct Table {
+    pub(crate) num_states: u32,
+    pub(crate) alphabet: Vec<char>,
+    pub(crate) accept_states: Vec<u32>,
+    pub(crate) transitions: Vec<(u32, Option<char>, u32)>,
+}
+
+impl Table {
+    pub(crate) fn parse(input: &str) -> Result<Self, &'static str> {
+        let mut lines = input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .peekable();
+
+        // the header is left out if the alphabet is empty
+        let header = match lines.peek() {
+            Some(line) if line.split('|').next().unwrap().trim().is_empty() => lines.next(),
+            _ => None,
+        };
+        let is_separator = |line: &str| {
+            line.chars()
+                .all(|c| c.is_whitespace() || matches!(c, '-' | '+' | '|'))
+        };
+        let lines = lines.filter(|line| header.is_none() || !is_separator(line));
+
+        let mut symbols = Vec::new();
+        for cell in header
+            .into_iter()
+            .flat_map(|header| header.split('|').skip(1))
+        {
+            let symbol = parse_symbol_cell(cell.trim())?;
+            if symbols.contains(&symbol) {
+                return Err("The table has a symbol in more than one column");
+            }
+            symbols.push(symbol);
+        }
+
+        // the names of the states and the cells of their rows
+        let mut rows: Vec<(&str, Vec<&str>)> = Vec::new();
+        let mut start = None;
+        let mut accept = Vec::new();
+
+        for line in lines {
+            let mut cells = line.split('|').map(str::trim);
+            let mut label = cells.next().unwrap_or_default();
+            let (mut is_start, mut is_accept) = (false, false);
+
+            loop {
+                if let Some(rest) = label.strip_prefix('→').or(label.strip_prefix("->")) {
+                    is_start = true;
+                    label = rest.trim_start();
+                } else if let Some(rest) = label.strip_prefix('*') {
+                    is_accept = true;
+                    label = rest.trim_start();
+                } else {
+                    break;
+                }
+            }
+
+            if label.is_empty() || label.contains(char::is_whitespace) {
+                return Err("Every row of the table must start with a state name");
+            }
+            if rows.iter().any(|(name, _)| *name == label) {
+                return Err("The table has a state in more than one row");
+            }
+            if is_start && start.replace(rows.len()).is_some() {
+                return Err("The table has more than one start state");
+            }
+            if is_accept {
+                accept.push(rows.len());
+            }
+
+            let cells: Vec<&str> = cells.collect();
+            if cells.len() != symbols.len() {
+                return Err("Every row of the table must have a cell for every symbol");
+            }
+            rows.push((label, cells));
+        }
+
+        if rows.is_empty() {
+            return Err("The table has no states");
+        }
+
+        // the start state comes first, the rest keep their order
+        let start = start.unwrap_or(0);
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        order[..=start].rotate_right(1);
+        let lookup_table: HashMap<&str, u32> = order
+            .iter()
+            .enumerate()
+            .map(|(state, &row)| (rows[row].0, state as u32))
+            .collect();
+
+        let mut table = Table {
+            num_states: rows.len() as u32,
+            alphabet: symbols.iter().filter_map(|symbol| *symbol).collect(),
+            accept_states: accept
+                .iter()
+                .map(|&row| lookup_table[rows[row].0])
+                .collect(),
+            transitions: Vec::new(),
+        };
+        table.accept_states.sort();
+
+        for (name, cells) in rows.iter() {
+            let source = lookup_table[name];
+
+            for (symbol, cell) in symbols.iter().zip(cells.iter()) {
+                let targets = match *cell {
+                    "∅" | "-" | "" => continue,
+                    cell => match cell.strip_prefix('{').and_then(|c| c.strip_suffix('}')) {
+                        Some(set) => set
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|t| !t.is_empty())
+                            .collect(),
+                        None => vec![cell],
+                    },
+                };
+
+                for target in targets {
+                    let target = lookup_table
+                        .get(target)
+                        .ok_or("The table has a target state without a row")?;
+                    table.transitions.push((source, *symbol, *target));
+                }
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_parse() {
+        let table = Table::parse(
+            "       | a        | b  | ε
+             ---------------------------
+             *q1    | {q0, q1} | -  | ∅
+             ->*q0  | q1       | {} | q1",
+        )
+        .unwrap();
+
+        assert_eq!(2, table.num_states);
+        assert_eq!(vec!['a', 'b'], table.alphabet);
+        assert_eq!(vec![0, 1], table.accept_states);
+        assert_eq!(
+            vec![
+                (1, Some('a'), 0),
+                (1, Some('a'), 1),
+                (0, Some('a'), 1),
+                (0, None, 1)
+            ],
+            table.transitions
+        );
+    }
+
+    #[test]
+    fn table_symbol_cells() {
+        assert_eq!("a", symbol_cell("a"));
+        assert_eq!("\\u{3b5}", symbol_cell("ε"));
+        assert_eq!("\\u{7c}", symbol_cell("|"));
+        assert_eq!("\\u{20}", symbol_cell(" "));
+
+        let table =
+            Table::parse("  | - | \\u{7c} | \\u{3b5} | ε\n-----\n→ 0 | 0 | - | 0 | -").unwrap();
+        assert_eq!(vec!['-', '|', 'ε'], table.alphabet);
+        assert_eq!(
+            vec![(0, Some('-'), 0), (0, Some('ε'), 0)],
+            table.transitions
+        );
+        assert!(Table::parse("  | \\u{d800}\n→ 0 | 0").is_err());
+    }
+
+    #[test]
+    fn table_parse_errors() {
+        assert!(Table::parse("").is_err());
+        assert!(Table::parse("  | a").is_err());
+        assert!(Table::parse("→ 0\n  1").is_ok());
+        assert!(Table::parse("  | ab\n→ 0 | 0").is_err());
+        assert!(Table::parse("  | a\n→ 0 | 1").is_err());
+        assert!(Table::parse("  | a\n→ 0 | 0 | 0").is_err());
+        assert!(Table::parse("  | a\n→ 0 | 0\n→ 1 | 0").is_err());
+        assert!(Table::parse("  | a\n→ 0 | 0\n0 | 0").is_err());
+        assert!(Table::parse("  | a | a\n→ 0 | 0 | 0").is_err());
+    }
+}
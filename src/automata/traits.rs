@@ -6,11 +6,15 @@ pub trait State {
 }
 
 pub trait Alphabet {
-    fn add_symbol(&mut self, symbol: char);
-    fn remove_symbol(&mut self, symbol: char);
+    type Symbol;
+
+    fn add_symbol(&mut self, symbol: Self::Symbol);
+    fn remove_symbol(&mut self, symbol: Self::Symbol);
 }
 
 pub trait Transition {
-    fn add_transition(&mut self, source: &(u32, char), target: u32) -> Result<(), &'static str>;
-    // fn remove_transition(&mut self, source: &(u32, char), target: u32);
+    type Source;
+
+    fn add_transition(&mut self, source: &Self::Source, target: u32) -> Result<(), &'static str>;
+    // fn remove_transition(&mut self, source: &Self::Source, target: u32);
 }
@@ -0,0 +1,250 @@
+use std::fmt;
+
+const MAX_SCALAR_VALUE: u32 = 0x10FFFF;
+const SURROGATE_START: u32 = 0xD800;
+const SURROGATE_END: u32 = 0xDFFF;
+
+/// A range of bytes that a single byte of an UTF-8 sequence can be in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ByteRange {
+    pub start: u8,
+    pub end: u8,
+}
+
+impl ByteRange {
+    #[inline]
+    pub fn contains(&self, byte: u8) -> bool {
+        self.start <= byte && byte <= self.end
+    }
+}
+
+/// A sequence of byte ranges that matches the UTF-8 encoding
+/// of every char inside of a contiguous range of chars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Utf8Sequence {
+    ranges: [ByteRange; 4],
+    len: usize,
+}
+
+impl Utf8Sequence {
+    #[inline]
+    pub fn as_slice(&self) -> &[ByteRange] {
+        &self.ranges[..self.len]
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if the given bytes are matched by the sequence
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        bytes.len() == self.len
+            && self
+                .as_slice()
+                .iter()
+                .zip(bytes)
+                .all(|(range, &byte)| range.contains(byte))
+    }
+
+    fn from_encodings(start: &[u8], end: &[u8]) -> Self {
+        let mut ranges = [ByteRange { start: 0, end: 0 }; 4];
+
+        for (index, (&start, &end)) in start.iter().zip(end).enumerate() {
+            ranges[index] = ByteRange { start, end };
+        }
+
+        Utf8Sequence {
+            ranges,
+            len: start.len(),
+        }
+    }
+}
+
+impl fmt::Display for Utf8Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for range in self.as_slice() {
+            if range.start == range.end {
+                write!(f, "[{:02X}]", range.start)?;
+            } else {
+                write!(f, "[{:02X}-{:02X}]", range.start, range.end)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the UTF-8 sequences that together match
+/// every char inside of a given range of chars and nothing else.
+/// The sequences are generated in order and don't overlap.
+///
+/// # Example
+/// ```rust
+/// use fmsi::automata::utf8::Utf8Sequences;
+///
+/// let sequences: Vec<String> = Utf8Sequences::new('\u{0}', '\u{FFFF}')
+///     .map(|sequence| sequence.to_string())
+///     .collect();
+///
+/// assert_eq!(
+///     vec![
+///         "[00-7F]",
+///         "[C2-DF][80-BF]",
+///         "[E0][A0-BF][80-BF]",
+///         "[E1-EC][80-BF][80-BF]",
+///         "[ED][80-9F][80-BF]",
+///         "[EE-EF][80-BF][80-BF]",
+///     ],
+///     sequences
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Utf8Sequences {
+    stack: Vec<(u32, u32)>,
+}
+
+impl Utf8Sequences {
+    pub fn new(start: char, end: char) -> Self {
+        let mut stack = Vec::new();
+        if start <= end {
+            stack.push((start as u32, end as u32));
+        }
+
+        Utf8Sequences { stack }
+    }
+}
+
+impl Iterator for Utf8Sequences {
+    type Item = Utf8Sequence;
+
+    fn next(&mut self) -> Option<Utf8Sequence> {
+        'ranges: while let Some((mut start, mut end)) = self.stack.pop() {
+            // surrogates can't be encoded, so skip over them
+            if start < SURROGATE_START && end > SURROGATE_END {
+                self.stack.push((SURROGATE_END + 1, end));
+                end = SURROGATE_START - 1;
+            }
+            start = if (SURROGATE_START..=SURROGATE_END).contains(&start) {
+                SURROGATE_END + 1
+            } else {
+                start
+            };
+            end = if (SURROGATE_START..=SURROGATE_END).contains(&end) {
+                SURROGATE_START - 1
+            } else {
+                end
+            };
+            if start > end || start > MAX_SCALAR_VALUE {
+                continue;
+            }
+
+            // split the range so that all chars in it
+            // have encodings of the same length
+            for max in [0x7F, 0x7FF, 0xFFFF] {
+                if start <= max && max < end {
+                    self.stack.push((max + 1, end));
+                    self.stack.push((start, max));
+                    continue 'ranges;
+                }
+            }
+
+            if end <= 0x7F {
+                let range = ByteRange {
+                    start: start as u8,
+                    end: end as u8,
+                };
+                return Some(Utf8Sequence::from_encodings(&[range.start], &[range.end]));
+            }
+
+            // split the range until every continuation byte
+            // can take up it's whole range of values
+            for index in 1..4 {
+                let mask: u32 = (1 << (6 * index)) - 1;
+
+                if start & !mask != end & !mask {
+                    if start & mask != 0 {
+                        self.stack.push(((start | mask) + 1, end));
+                        self.stack.push((start, start | mask));
+                        continue 'ranges;
+                    }
+                    if end & mask != mask {
+                        self.stack.push((end & !mask, end));
+                        self.stack.push((start, (end & !mask) - 1));
+                        continue 'ranges;
+                    }
+                }
+            }
+
+            let mut start_bytes = [0; 4];
+            let mut end_bytes = [0; 4];
+            let start = char::from_u32(start).unwrap().encode_utf8(&mut start_bytes);
+            let end = char::from_u32(end).unwrap().encode_utf8(&mut end_bytes);
+
+            return Some(Utf8Sequence::from_encodings(start.as_bytes(), end.as_bytes()));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches_any(sequences: &[Utf8Sequence], symbol: char) -> bool {
+        let mut bytes = [0; 4];
+        let bytes = symbol.encode_utf8(&mut bytes).as_bytes();
+
+        sequences.iter().filter(|s| s.matches(bytes)).count() == 1
+    }
+
+    #[test]
+    fn utf8_sequences_single_byte() {
+        let sequences: Vec<Utf8Sequence> = Utf8Sequences::new('a', 'z').collect();
+
+        assert_eq!(1, sequences.len());
+        assert_eq!("[61-7A]", sequences[0].to_string());
+    }
+
+    #[test]
+    fn utf8_sequences_cover_range() {
+        let ranges = [
+            ('\0', char::MAX),
+            ('a', 'ž'),
+            ('\u{7F}', '\u{80}'),
+            ('\u{D7FF}', '\u{E000}'),
+            ('\u{FFFF}', '\u{10000}'),
+            ('\u{1F600}', '\u{1F64F}'),
+        ];
+
+        for (start, end) in ranges {
+            let sequences: Vec<Utf8Sequence> = Utf8Sequences::new(start, end).collect();
+
+            // sample chars inside and around the range
+            for code in (start as u32).saturating_sub(300)..=(end as u32 + 300).min(0x10FFFF) {
+                if code % 7 != 0 && code > start as u32 + 300 && code + 300 < end as u32 {
+                    continue;
+                }
+
+                if let Some(symbol) = char::from_u32(code) {
+                    let inside = start <= symbol && symbol <= end;
+                    assert_eq!(inside, matches_any(&sequences, symbol), "{:?}", symbol);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn utf8_sequences_surrogates() {
+        let sequences: Vec<String> = Utf8Sequences::new('\u{D7FF}', '\u{E000}')
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(vec!["[ED][9F][BF]", "[EE][80][80]"], sequences);
+    }
+}
@@ -1 +1,18 @@
-pub const EMPTY_STRING: char = '\0';
+pub mod regex;
+
+pub use regex::Regex;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// A symbol that automata can transition on.
+///
+/// Automata built by the regex front end use `char`,
+/// but any type that can be compared, ordered and hashed
+/// works, like `u8` or an enum of protocol tokens.
+pub trait Symbol: Clone + Eq + Hash + Ord + Debug {}
+
+impl<T: Clone + Eq + Hash + Ord + Debug> Symbol for T {}
+
+/// Label of an nfa transition that doesn't consume a symbol.
+/// Nfa transitions on a symbol are labeled with `Some(symbol)`.
+pub const EMPTY_STRING: Option<char> = None;
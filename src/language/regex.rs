@@ -0,0 +1,581 @@
+use std::fmt;
+
+mod classes;
+mod derivative;
+#[cfg(feature = "serde")]
+mod schema;
+
+pub use classes::CharClasses;
+
+/// The greatest char, used as the upper bound of negated classes
+pub const MAX_CHAR: char = char::MAX;
+
+/// The greatest number of chars a class can have
+/// for it to be expanded in to an alphabet
+pub const MAX_CLASS_SIZE: usize = 1 << 12;
+
+/// A regular expression parsed in to a syntax tree.
+///
+/// The parser is also used by `Nfa::from` and `Dfa::from`. It accepts
+/// symbols, brackets, `|` and `*`, char classes like `[a-z_]`, negated
+/// classes like `[^0-9]`, `.` for any char, `\` for escaping a special
+/// char and `\u{3b5}` for a char by it's code point. The empty regex
+/// and `()` match the empty string, and a class without any chars,
+/// like `[^\u{0}-\u{10ffff}]`, is the empty set. Every regex is
+/// displayed in this syntax, so it's parsed back in to the same regex.
+///
+/// Automata are built over the chars of the regex, so classes with
+/// more than `MAX_CLASS_SIZE` chars, like `.` and most negated classes,
+/// can be parsed and derived with `Regex::derivative`, but not built
+/// in to an nfa or a dfa. Such a regex can be rewritten over the
+/// representatives of it's `CharClasses` first.
+///
+/// With the `serde` feature, deserialization fails if a class
+/// breaks the invariant on it's ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "schema::RegexSchema"))]
+pub enum Regex {
+    EmptySet,
+    EmptyString,
+    Symbol(char),
+    /// Sorted, non-overlapping and non-adjacent inclusive ranges
+    Class(Vec<(char, char)>),
+    Concat(Box<Regex>, Box<Regex>),
+    Union(Box<Regex>, Box<Regex>),
+    KleeneStar(Box<Regex>),
+}
+
+impl Regex {
+    /// Parses the given regular expression
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::language::Regex;
+    ///
+    /// let regex = Regex::parse("a[0-9]*").unwrap();
+    ///
+    /// assert_eq!(
+    ///     Regex::Concat(
+    ///         Box::new(Regex::Symbol('a')),
+    ///         Box::new(Regex::KleeneStar(Box::new(Regex::Class(vec![('0', '9')])))),
+    ///     ),
+    ///     regex
+    /// );
+    /// ```
+    pub fn parse(expression: &str) -> Result<Self, &'static str> {
+        let mut parser = Parser {
+            tokens: expression.chars().collect(),
+            position: 0,
+        };
+
+        let regex = parser.parse_union()?;
+
+        match parser.peek() {
+            None => Ok(regex),
+            Some(')') => Err("Brackets don't match"),
+            Some(_) => Err("Regex is not in the correct form"),
+        }
+    }
+
+    /// Returns a class that contains the given ranges
+    pub fn class(ranges: &[(char, char)]) -> Self {
+        let mut ranges: Vec<(char, char)> = ranges
+            .iter()
+            .map(|&(start, end)| if start <= end { (start, end) } else { (end, start) })
+            .collect();
+        ranges.sort();
+
+        // merge overlapping and adjacent ranges
+        let mut merged: Vec<(char, char)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if next_char(last.1).is_none_or(|next| start <= next) => {
+                    last.1 = last.1.max(end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Regex::Class(merged)
+    }
+
+    /// Returns a class that contains every char
+    /// which isn't in any of the given ranges
+    pub fn negated_class(ranges: &[(char, char)]) -> Self {
+        let Regex::Class(ranges) = Regex::class(ranges) else {
+            unreachable!()
+        };
+
+        let mut negated = Vec::with_capacity(ranges.len() + 1);
+        let mut start = Some('\0');
+
+        for (first, last) in ranges {
+            if let Some(start) = start {
+                if start < first {
+                    negated.push((start, previous_char(first).unwrap()));
+                }
+            }
+            start = next_char(last);
+        }
+
+        if let Some(start) = start {
+            negated.push((start, MAX_CHAR));
+        }
+
+        Regex::Class(negated)
+    }
+
+    /// Returns every char used by the regex, sorted.
+    /// Classes are expanded in to their chars, so the alphabet
+    /// can't be computed if a class has more than `MAX_CLASS_SIZE` of them.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::language::Regex;
+    ///
+    /// let regex = Regex::parse("b[0-2]*a|b").unwrap();
+    ///
+    /// assert_eq!(vec!['0', '1', '2', 'a', 'b'], regex.alphabet().unwrap());
+    /// assert!(Regex::parse("a.").unwrap().alphabet().is_err());
+    /// ```
+    pub fn alphabet(&self) -> Result<Vec<char>, &'static str> {
+        let mut alphabet = Vec::new();
+        let mut stack = vec![self];
+
+        while let Some(regex) = stack.pop() {
+            match regex {
+                Regex::EmptySet | Regex::EmptyString => {}
+                Regex::Symbol(_) | Regex::Class(_) => alphabet.extend(regex.chars()?),
+                Regex::Concat(first, second) | Regex::Union(first, second) => {
+                    stack.push(first);
+                    stack.push(second);
+                }
+                Regex::KleeneStar(inner) => stack.push(inner),
+            }
+        }
+
+        alphabet.sort();
+        alphabet.dedup();
+        Ok(alphabet)
+    }
+
+    /// Returns the number of chars and operators in the regex,
+    /// counting every char of a class
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::language::Regex;
+    ///
+    /// assert_eq!(3, Regex::parse("ab").unwrap().size());
+    /// assert_eq!(11, Regex::parse("[0-9]*").unwrap().size());
+    /// ```
+    pub fn size(&self) -> usize {
+        let mut size = 0;
+        let mut stack = vec![self];
+
+        while let Some(regex) = stack.pop() {
+            size += match regex {
+                Regex::Class(ranges) => ranges
+                    .iter()
+                    .map(|&(start, end)| range_size(start, end))
+                    .sum(),
+                _ => 1,
+            };
+
+            match regex {
+                Regex::Concat(first, second) | Regex::Union(first, second) => {
+                    stack.push(first);
+                    stack.push(second);
+                }
+                Regex::KleeneStar(inner) => stack.push(inner),
+                _ => {}
+            }
+        }
+
+        size
+    }
+
+    /// Returns the chars matched by a symbol or a class, sorted.
+    /// Returns an error if the class has more than `MAX_CLASS_SIZE` chars.
+    pub fn chars(&self) -> Result<Vec<char>, &'static str> {
+        match self {
+            Regex::Symbol(symbol) => Ok(vec![*symbol]),
+            Regex::Class(ranges) => {
+                let size: usize = ranges.iter().map(|&(start, end)| range_size(start, end)).sum();
+                if size > MAX_CLASS_SIZE {
+                    return Err("Class has too many chars to be expanded");
+                }
+
+                Ok(ranges.iter().flat_map(|&(start, end)| start..=end).collect())
+            }
+            _ => Err("Only symbols and classes match single chars"),
+        }
+    }
+
+    /// Returns true if the class or symbol contains the given char
+    pub fn contains(&self, symbol: char) -> bool {
+        match self {
+            Regex::Symbol(s) => *s == symbol,
+            Regex::Class(ranges) => ranges
+                .iter()
+                .any(|&(start, end)| start <= symbol && symbol <= end),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Regex::EmptySet => {
+                write!(f, "[^")?;
+                write_symbol(f, '\0', true)?;
+                write!(f, "-")?;
+                write_symbol(f, MAX_CHAR, true)?;
+                write!(f, "]")
+            }
+            Regex::EmptyString => write!(f, "()"),
+            Regex::Symbol(symbol) => write_symbol(f, *symbol, false),
+            Regex::Class(ranges) if ranges.is_empty() => write!(f, "{}", Regex::EmptySet),
+            Regex::Class(ranges) => {
+                if ranges.len() == 1 && ranges[0] == ('\0', MAX_CHAR) {
+                    return write!(f, ".");
+                }
+
+                write!(f, "[")?;
+                for &(start, end) in ranges {
+                    write_symbol(f, start, true)?;
+                    if start != end {
+                        write!(f, "-")?;
+                        write_symbol(f, end, true)?;
+                    }
+                }
+                write!(f, "]")
+            }
+            Regex::Concat(first, second) => {
+                write_operand(f, first, matches!(**first, Regex::Union(..)))?;
+                write_operand(f, second, matches!(**second, Regex::Union(..)))
+            }
+            Regex::Union(first, second) => write!(f, "{}|{}", first, second),
+            Regex::KleeneStar(inner) => {
+                let bracket = matches!(**inner, Regex::Concat(..) | Regex::Union(..));
+                write_operand(f, inner, bracket)?;
+                write!(f, "*")
+            }
+        }
+    }
+}
+
+fn write_operand(f: &mut fmt::Formatter<'_>, regex: &Regex, bracket: bool) -> fmt::Result {
+    if bracket {
+        write!(f, "({})", regex)
+    } else {
+        write!(f, "{}", regex)
+    }
+}
+
+fn write_symbol(f: &mut fmt::Formatter<'_>, symbol: char, in_class: bool) -> fmt::Result {
+    // chars that aren't printable are written by their code point
+    if !matches!(symbol, '\\' | '\'' | '"') && symbol.escape_debug().nth(1).is_some() {
+        return write!(f, "\\u{{{:x}}}", symbol as u32);
+    }
+
+    let special = if in_class {
+        matches!(symbol, ']' | '\\' | '-' | '^')
+    } else {
+        matches!(symbol, '(' | ')' | '|' | '*' | '[' | ']' | '.' | '\\')
+    };
+
+    if special {
+        write!(f, "\\")?;
+    }
+    write!(f, "{}", symbol)
+}
+
+/// Returns the number of chars inside of an inclusive range
+fn range_size(start: char, end: char) -> usize {
+    let size = (end as u32 - start as u32 + 1) as usize;
+
+    // surrogates aren't chars
+    if start <= '\u{D7FF}' && end >= '\u{E000}' {
+        size - 0x800
+    } else {
+        size
+    }
+}
+
+/// Returns the char that follows the given one, skipping surrogates
+pub(crate) fn next_char(symbol: char) -> Option<char> {
+    match symbol {
+        '\u{D7FF}' => Some('\u{E000}'),
+        MAX_CHAR => None,
+        _ => char::from_u32(symbol as u32 + 1),
+    }
+}
+
+/// Returns the char that precedes the given one, skipping surrogates
+pub(crate) fn previous_char(symbol: char) -> Option<char> {
+    match symbol {
+        '\u{E000}' => Some('\u{D7FF}'),
+        '\0' => None,
+        _ => char::from_u32(symbol as u32 - 1),
+    }
+}
+
+struct Parser {
+    tokens: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    /// Returns the char after a `\`, which is either a
+    /// code point like `\u{3b5}` or the char itself
+    fn escaped(&mut self) -> Result<char, &'static str> {
+        let symbol = self.next().ok_or("Regex ends with an escape")?;
+        if symbol != 'u' || self.peek() != Some('{') {
+            return Ok(symbol);
+        }
+
+        let end = self.tokens[self.position..]
+            .iter()
+            .position(|&token| token == '}')
+            .ok_or("Regex has an invalid code point")?;
+        let code: String = self.tokens[self.position + 1..self.position + end]
+            .iter()
+            .collect();
+        self.position += end + 1;
+
+        u32::from_str_radix(&code, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or("Regex has an invalid code point")
+    }
+
+    fn parse_union(&mut self) -> Result<Regex, &'static str> {
+        let mut regex = self.parse_concat()?;
+
+        while self.peek() == Some('|') {
+            self.next();
+            let other = self.parse_concat()?;
+            regex = Regex::Union(Box::new(regex), Box::new(other));
+        }
+
+        Ok(regex)
+    }
+
+    fn parse_concat(&mut self) -> Result<Regex, &'static str> {
+        let mut regex: Option<Regex> = None;
+
+        while let Some(token) = self.peek() {
+            if token == '|' || token == ')' {
+                break;
+            }
+
+            let other = self.parse_star()?;
+            regex = Some(match regex {
+                Some(regex) => Regex::Concat(Box::new(regex), Box::new(other)),
+                None => other,
+            });
+        }
+
+        match (regex, self.peek()) {
+            (Some(regex), _) => Ok(regex),
+            // an alternative can't be empty, but brackets can
+            (None, Some(')')) if self.position > 0 && self.tokens[self.position - 1] == '(' => {
+                Ok(Regex::EmptyString)
+            }
+            (None, _) if self.tokens.is_empty() => Ok(Regex::EmptyString),
+            (None, _) => Err("Regex contains an invalid pattern"),
+        }
+    }
+
+    fn parse_star(&mut self) -> Result<Regex, &'static str> {
+        let mut regex = self.parse_atom()?;
+
+        while self.peek() == Some('*') {
+            self.next();
+            // a** is the same as a*
+            if !matches!(regex, Regex::KleeneStar(_)) {
+                regex = Regex::KleeneStar(Box::new(regex));
+            }
+        }
+
+        Ok(regex)
+    }
+
+    fn parse_atom(&mut self) -> Result<Regex, &'static str> {
+        match self.next() {
+            Some('(') => {
+                let regex = self.parse_union()?;
+                match self.next() {
+                    Some(')') => Ok(regex),
+                    _ => Err("Brackets don't match"),
+                }
+            }
+            Some('[') => self.parse_class(),
+            Some('.') => Ok(Regex::Class(vec![('\0', MAX_CHAR)])),
+            Some('\\') => self.escaped().map(Regex::Symbol),
+            Some('*') => Err("Regex contains an invalid pattern"),
+            Some(symbol) => Ok(Regex::Symbol(symbol)),
+            None => Err("Regex is not in the correct form"),
+        }
+    }
+
+    fn parse_class(&mut self) -> Result<Regex, &'static str> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.next();
+        }
+
+        let mut ranges: Vec<(char, char)> = Vec::new();
+        let mut first = true;
+
+        loop {
+            let start = match self.next() {
+                None => return Err("Class is missing a closing bracket"),
+                Some(']') if !first => break,
+                Some('\\') => self.escaped()?,
+                Some(symbol) => symbol,
+            };
+            first = false;
+
+            // a '-' that isn't followed by ']' makes a range
+            let is_range = self.peek() == Some('-')
+                && self.tokens.get(self.position + 1).is_some_and(|&s| s != ']');
+
+            if is_range {
+                self.next();
+                let end = match self.next() {
+                    Some('\\') => self.escaped()?,
+                    Some(symbol) => symbol,
+                    None => return Err("Class is missing a closing bracket"),
+                };
+
+                if end < start {
+                    return Err("Class range is out of order");
+                }
+                ranges.push((start, end));
+            } else {
+                ranges.push((start, start));
+            }
+        }
+
+        let class = if negated {
+            Regex::negated_class(&ranges)
+        } else {
+            Regex::class(&ranges)
+        };
+
+        match class {
+            Regex::Class(ranges) if ranges.is_empty() => Ok(Regex::EmptySet),
+            class => Ok(class),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbol(symbol: char) -> Box<Regex> {
+        Box::new(Regex::Symbol(symbol))
+    }
+
+    #[test]
+    fn regex_parsing() {
+        let regex = Regex::parse("a|(ab|b)*").unwrap();
+        let expected = Regex::Union(
+            symbol('a'),
+            Box::new(Regex::KleeneStar(Box::new(Regex::Union(
+                Box::new(Regex::Concat(symbol('a'), symbol('b'))),
+                symbol('b'),
+            )))),
+        );
+
+        assert_eq!(expected, regex);
+        assert_eq!(Regex::EmptyString, Regex::parse("").unwrap());
+        assert_eq!(Regex::EmptyString, Regex::parse("()").unwrap());
+        assert_eq!(Regex::Symbol('*'), Regex::parse("\\*").unwrap());
+        assert_eq!(Regex::Symbol('u'), Regex::parse("\\u").unwrap());
+        assert_eq!(Regex::Symbol('ε'), Regex::parse("\\u{3b5}").unwrap());
+        assert_eq!(
+            Regex::Class(vec![('\0', 'a')]),
+            Regex::parse("[\\u{0}-a]").unwrap()
+        );
+    }
+
+    #[test]
+    fn regex_parsing_errors() {
+        assert!(Regex::parse("(*ab)").is_err());
+        assert!(Regex::parse("(|ab)").is_err());
+        assert!(Regex::parse("(ab|)").is_err());
+        assert!(Regex::parse("a||b").is_err());
+        assert!(Regex::parse("(ab|b)*)").is_err());
+        assert!(Regex::parse("((ab|b)*").is_err());
+        assert!(Regex::parse("[a-").is_err());
+        assert!(Regex::parse("[z-a]").is_err());
+        assert!(Regex::parse("ab\\").is_err());
+        assert!(Regex::parse("\\u{d800}").is_err());
+        assert!(Regex::parse("\\u{3b5").is_err());
+    }
+
+    #[test]
+    fn regex_classes() {
+        assert_eq!(
+            Regex::Class(vec![('-', '-'), ('0', '9'), ('a', 'f')]),
+            Regex::parse("[a-f0-56-9-]").unwrap()
+        );
+        assert_eq!(
+            Regex::Class(vec![('0', '9'), ('a', 'z')]),
+            Regex::parse("[a-mn-z0-9]").unwrap()
+        );
+        assert_eq!(Regex::Class(vec![(']', ']')]), Regex::parse("[]]").unwrap());
+        assert_eq!(
+            Regex::Class(vec![('\0', '`'), ('{', MAX_CHAR)]),
+            Regex::parse("[^a-z]").unwrap()
+        );
+
+        let regex = Regex::parse("[^b]").unwrap();
+        assert!(regex.contains('a'));
+        assert!(regex.contains('č'));
+        assert!(!regex.contains('b'));
+    }
+
+    #[test]
+    fn regex_display() {
+        for expression in ["a|(ab|b)*", "a[0-9]*.", "(a|b)c", "[\\]a]\\*", "a(b*c)*"] {
+            let regex = Regex::parse(expression).unwrap();
+            assert_eq!(expression, regex.to_string());
+        }
+
+        // the empty string and the empty set are read back as themselves
+        for regex in [
+            Regex::EmptyString,
+            Regex::EmptySet,
+            Regex::Concat(symbol('a'), Box::new(Regex::EmptyString)),
+            Regex::KleeneStar(Box::new(Regex::Union(
+                Box::new(Regex::EmptySet),
+                symbol('b'),
+            ))),
+        ] {
+            assert_eq!(regex, Regex::parse(&regex.to_string()).unwrap());
+        }
+        assert_eq!("()", Regex::EmptyString.to_string());
+        assert_eq!("[^\\u{0}-\\u{10ffff}]", Regex::EmptySet.to_string());
+        assert_eq!(
+            "a\\u{7}[\\u{0}-\\u{1f}]",
+            Regex::parse("a\u{7}[\0-\u{1f}]").unwrap().to_string()
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use super::{next_char, Regex};
+use std::collections::HashMap;
+
+/// Splits every char in to classes of chars that each symbol and
+/// class of a regex either contains all of or none of, and picks
+/// one char of every class to stand for it.
+///
+/// A regex rewritten over the representatives has a small alphabet,
+/// so even one with `.` or negated classes can be built in to an
+/// automaton, which then runs on text mapped to the representatives.
+/// Chars that the regex doesn't mention share a representative,
+/// which isn't in the alphabet unless a class contains it.
+///
+/// # Example
+/// ```rust
+/// use fmsi::language::regex::CharClasses;
+/// use fmsi::language::Regex;
+/// use fmsi::Nfa;
+///
+/// let regex = Regex::parse("a.[^0-9]").unwrap();
+/// let classes = CharClasses::new(&regex);
+/// let dfa = Nfa::from_regex(&classes.rewrite(&regex)).unwrap().to_dfa();
+///
+/// assert!(dfa.run(&classes.map("abc")).unwrap());
+/// assert!(dfa.run(&classes.map("a5ž")).unwrap());
+/// assert!(!dfa.run(&classes.map("bbc")).unwrap());
+/// assert!(!dfa.run(&classes.map("ab1")).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharClasses {
+    /// Sorted first chars of intervals whose chars are in the same class
+    starts: Vec<char>,
+    /// The representative of the class of every interval
+    representatives: Vec<char>,
+}
+
+impl CharClasses {
+    /// Returns the classes of chars that the regex can't tell apart
+    pub fn new(regex: &Regex) -> Self {
+        let mut atoms = Vec::new();
+        regex.atoms(&mut atoms);
+        atoms.sort();
+        atoms.dedup();
+
+        // every range of an atom starts an interval,
+        // and so does the char after it
+        let mut starts = vec!['\0'];
+        for atom in atoms.iter() {
+            let ranges = match atom {
+                Regex::Symbol(symbol) => vec![(*symbol, *symbol)],
+                Regex::Class(ranges) => ranges.clone(),
+                _ => unreachable!(),
+            };
+            for (start, end) in ranges {
+                starts.push(start);
+                starts.extend(next_char(end));
+            }
+        }
+        starts.sort();
+        starts.dedup();
+
+        // intervals in the same atoms share the representative
+        // of the first of them
+        let mut signatures: HashMap<Vec<bool>, char> = HashMap::new();
+        let representatives = starts
+            .iter()
+            .map(|&start| {
+                let signature = atoms.iter().map(|atom| atom.contains(start)).collect();
+                *signatures.entry(signature).or_insert(start)
+            })
+            .collect();
+
+        CharClasses {
+            starts,
+            representatives,
+        }
+    }
+
+    /// Returns the char that stands for the class of the given char
+    pub fn representative(&self, symbol: char) -> char {
+        let index = self.starts.partition_point(|&start| start <= symbol) - 1;
+        self.representatives[index]
+    }
+
+    /// Replaces every char of the text with it's representative
+    pub fn map(&self, text: &str) -> String {
+        text.chars()
+            .map(|symbol| self.representative(symbol))
+            .collect()
+    }
+
+    /// Returns the regex with every symbol and class replaced
+    /// by the representatives of the chars it contains
+    pub fn rewrite(&self, regex: &Regex) -> Regex {
+        match regex {
+            Regex::Symbol(symbol) => Regex::Symbol(self.representative(*symbol)),
+            Regex::Class(_) => {
+                let mut representatives: Vec<(char, char)> = self
+                    .starts
+                    .iter()
+                    .zip(self.representatives.iter())
+                    .filter(|(&start, _)| regex.contains(start))
+                    .map(|(_, &representative)| (representative, representative))
+                    .collect();
+                representatives.dedup();
+                Regex::class(&representatives)
+            }
+            Regex::Concat(first, second) => Regex::Concat(
+                Box::new(self.rewrite(first)),
+                Box::new(self.rewrite(second)),
+            ),
+            Regex::Union(first, second) => Regex::Union(
+                Box::new(self.rewrite(first)),
+                Box::new(self.rewrite(second)),
+            ),
+            Regex::KleeneStar(inner) => Regex::KleeneStar(Box::new(self.rewrite(inner))),
+            regex => regex.clone(),
+        }
+    }
+}
+
+impl Regex {
+    /// Pushes every symbol and class of the regex
+    fn atoms<'a>(&'a self, atoms: &mut Vec<&'a Regex>) {
+        match self {
+            Regex::EmptySet | Regex::EmptyString => {}
+            Regex::Symbol(_) | Regex::Class(_) => atoms.push(self),
+            Regex::Concat(first, second) | Regex::Union(first, second) => {
+                first.atoms(atoms);
+                second.atoms(atoms);
+            }
+            Regex::KleeneStar(inner) => inner.atoms(atoms),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::regex::MAX_CHAR;
+
+    #[test]
+    fn char_classes() {
+        let regex = Regex::parse("a[a-z]|[^0-9]").unwrap();
+        let classes = CharClasses::new(&regex);
+
+        // \0-/ and :-` and {-MAX are in [^0-9] only
+        assert_eq!('\0', classes.representative('/'));
+        assert_eq!('\0', classes.representative('`'));
+        assert_eq!('\0', classes.representative(MAX_CHAR));
+        assert_eq!('0', classes.representative('5'));
+        assert_eq!('a', classes.representative('a'));
+        assert_eq!('b', classes.representative('z'));
+
+        assert_eq!(
+            Regex::parse("a[ab]|[\\u{0}ab]").unwrap(),
+            classes.rewrite(&regex)
+        );
+        assert_eq!("\0abb0", classes.map("?abx9"));
+    }
+
+    #[test]
+    fn char_classes_of_symbols() {
+        // chars the regex doesn't use share the class of \0
+        let regex = Regex::parse("ab*|()").unwrap();
+        let classes = CharClasses::new(&regex);
+
+        assert_eq!(regex, classes.rewrite(&regex));
+        assert_eq!("ab\0\0", classes.map("abcž"));
+        assert_eq!("\0\0", CharClasses::new(&Regex::EmptySet).map("ab"));
+    }
+}
@@ -0,0 +1,271 @@
+use super::Regex;
+use std::collections::BTreeSet;
+
+impl Regex {
+    /// Returns the union of two regexes, similar regexes
+    /// get the same form: nested unions are flattened, their
+    /// alternatives are sorted and duplicates and empty sets removed.
+    pub fn union(first: Regex, second: Regex) -> Regex {
+        let mut alternatives = Vec::new();
+        first.alternatives(&mut alternatives);
+        second.alternatives(&mut alternatives);
+
+        alternatives.sort();
+        alternatives.dedup();
+
+        alternatives
+            .into_iter()
+            .reduce(|union, alternative| Regex::Union(Box::new(union), Box::new(alternative)))
+            .unwrap_or(Regex::EmptySet)
+    }
+
+    /// Returns the concatenation of two regexes,
+    /// simplified if one of them is the empty set or the empty string
+    pub fn concat(first: Regex, second: Regex) -> Regex {
+        match (first, second) {
+            (Regex::EmptySet, _) | (_, Regex::EmptySet) => Regex::EmptySet,
+            (Regex::EmptyString, regex) | (regex, Regex::EmptyString) => regex,
+            (first, second) => Regex::Concat(Box::new(first), Box::new(second)),
+        }
+    }
+
+    /// Returns the kleene star of a regex, simplified
+    /// if the regex is the empty set, the empty string or a kleene star
+    pub fn star(regex: Regex) -> Regex {
+        match regex {
+            Regex::EmptySet | Regex::EmptyString => Regex::EmptyString,
+            Regex::KleeneStar(inner) => Regex::KleeneStar(inner),
+            regex => Regex::KleeneStar(Box::new(regex)),
+        }
+    }
+
+    /// Rebuilds the regex with the simplifying constructors,
+    /// so that similar regexes become equal
+    pub fn normalized(&self) -> Regex {
+        match self {
+            Regex::Concat(first, second) => Regex::concat(first.normalized(), second.normalized()),
+            Regex::Union(first, second) => Regex::union(first.normalized(), second.normalized()),
+            Regex::KleeneStar(inner) => Regex::star(inner.normalized()),
+            regex => regex.clone(),
+        }
+    }
+
+    /// Returns true if the regex matches the empty string
+    pub fn nullable(&self) -> bool {
+        match self {
+            Regex::EmptySet | Regex::Symbol(_) | Regex::Class(_) => false,
+            Regex::EmptyString | Regex::KleeneStar(_) => true,
+            Regex::Concat(first, second) => first.nullable() && second.nullable(),
+            Regex::Union(first, second) => first.nullable() || second.nullable(),
+        }
+    }
+
+    /// Returns the Brzozowski derivative of the regex for the given char,
+    /// a regex that matches every suffix of a string starting with
+    /// the char, that the original regex matches.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::language::Regex;
+    ///
+    /// let regex = Regex::parse("ab|ac*").unwrap();
+    ///
+    /// assert_eq!("b|c*", regex.derivative('a').to_string());
+    /// assert_eq!(Regex::EmptySet, regex.derivative('b'));
+    /// ```
+    pub fn derivative(&self, symbol: char) -> Regex {
+        match self {
+            Regex::EmptySet | Regex::EmptyString => Regex::EmptySet,
+            Regex::Symbol(_) | Regex::Class(_) => {
+                if self.contains(symbol) {
+                    Regex::EmptyString
+                } else {
+                    Regex::EmptySet
+                }
+            }
+            Regex::Concat(first, second) => {
+                let derivative = Regex::concat(first.derivative(symbol), second.normalized());
+
+                if first.nullable() {
+                    Regex::union(derivative, second.derivative(symbol))
+                } else {
+                    derivative
+                }
+            }
+            Regex::Union(first, second) => {
+                Regex::union(first.derivative(symbol), second.derivative(symbol))
+            }
+            Regex::KleeneStar(inner) => {
+                Regex::concat(inner.derivative(symbol), Regex::star(inner.normalized()))
+            }
+        }
+    }
+
+    /// Returns the Antimirov partial derivatives of the regex for the
+    /// given char, a set of regexes whose union is the derivative.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::language::Regex;
+    ///
+    /// let regex = Regex::parse("ab|ac*").unwrap();
+    /// let derivatives: Vec<String> = regex
+    ///     .partial_derivatives('a')
+    ///     .iter()
+    ///     .map(|derivative| derivative.to_string())
+    ///     .collect();
+    ///
+    /// assert_eq!(vec!["b", "c*"], derivatives);
+    /// ```
+    pub fn partial_derivatives(&self, symbol: char) -> BTreeSet<Regex> {
+        match self {
+            Regex::EmptySet | Regex::EmptyString => BTreeSet::new(),
+            Regex::Symbol(_) | Regex::Class(_) => {
+                if self.contains(symbol) {
+                    BTreeSet::from([Regex::EmptyString])
+                } else {
+                    BTreeSet::new()
+                }
+            }
+            Regex::Concat(first, second) => {
+                let second_normalized = second.normalized();
+                let mut derivatives: BTreeSet<Regex> = first
+                    .partial_derivatives(symbol)
+                    .into_iter()
+                    .map(|derivative| Regex::concat(derivative, second_normalized.clone()))
+                    .collect();
+
+                if first.nullable() {
+                    derivatives.extend(second.partial_derivatives(symbol));
+                }
+                derivatives
+            }
+            Regex::Union(first, second) => {
+                let mut derivatives = first.partial_derivatives(symbol);
+                derivatives.extend(second.partial_derivatives(symbol));
+                derivatives
+            }
+            Regex::KleeneStar(inner) => {
+                let star = Regex::star(inner.normalized());
+                inner
+                    .partial_derivatives(symbol)
+                    .into_iter()
+                    .map(|derivative| Regex::concat(derivative, star.clone()))
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns true if the regex matches the whole input,
+    /// by deriving it for every char of the input
+    ///
+    /// # Example
+    /// ```rust
+    /// use fmsi::language::Regex;
+    ///
+    /// let regex = Regex::parse("a|(ab|b)*").unwrap();
+    ///
+    /// assert!(regex.matches("abbab"));
+    /// assert!(!regex.matches("aa"));
+    /// ```
+    pub fn matches(&self, input: &str) -> bool {
+        let mut regex = self.normalized();
+
+        for symbol in input.chars() {
+            if regex == Regex::EmptySet {
+                return false;
+            }
+            regex = regex.derivative(symbol);
+        }
+
+        regex.nullable()
+    }
+
+    /// Pushes the alternatives of a union, leaving out empty sets
+    fn alternatives(self, alternatives: &mut Vec<Regex>) {
+        match self {
+            Regex::Union(first, second) => {
+                first.alternatives(alternatives);
+                second.alternatives(alternatives);
+            }
+            Regex::EmptySet => {}
+            regex => alternatives.push(regex),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(expression: &str) -> Regex {
+        Regex::parse(expression).unwrap()
+    }
+
+    #[test]
+    fn regex_similarity() {
+        assert_eq!(parse("a|b|c").normalized(), parse("c|(b|a)|a").normalized());
+        assert_eq!(parse("a").normalized(), parse("a|a").normalized());
+        assert_eq!(Regex::EmptyString, parse("()*").normalized());
+        assert_eq!(parse("a*"), Regex::star(parse("a*")));
+        assert_eq!(parse("a"), Regex::concat(Regex::EmptyString, parse("a")));
+        assert_eq!(Regex::EmptySet, Regex::concat(parse("a"), Regex::EmptySet));
+        assert_eq!(parse("a"), Regex::union(Regex::EmptySet, parse("a")));
+    }
+
+    #[test]
+    fn regex_nullable() {
+        assert!(parse("a*").nullable());
+        assert!(parse("a|()").nullable());
+        assert!(parse("a*b*").nullable());
+        assert!(!parse("a*b").nullable());
+        assert!(!Regex::EmptySet.nullable());
+    }
+
+    #[test]
+    fn regex_derivative() {
+        let regex = parse("(ab|b)*");
+
+        assert_eq!(
+            Regex::concat(parse("b"), regex.normalized()),
+            regex.derivative('a')
+        );
+        assert_eq!(regex.normalized(), regex.derivative('b'));
+        assert_eq!(Regex::EmptySet, regex.derivative('c'));
+        assert_eq!(Regex::EmptyString, parse("[a-c]").derivative('b'));
+    }
+
+    #[test]
+    fn regex_partial_derivatives() {
+        let regex = parse("(ab|b)*");
+
+        assert_eq!(
+            BTreeSet::from([Regex::concat(parse("b"), regex.normalized())]),
+            regex.partial_derivatives('a')
+        );
+        assert_eq!(
+            BTreeSet::from([regex.normalized()]),
+            regex.partial_derivatives('b')
+        );
+        assert!(regex.partial_derivatives('c').is_empty());
+        assert_eq!(
+            BTreeSet::from([parse("b"), parse("c")]),
+            parse("ab|ac").partial_derivatives('a')
+        );
+        assert_eq!(
+            BTreeSet::from([parse("b|c")]),
+            parse("a(b|c)").partial_derivatives('a')
+        );
+    }
+
+    #[test]
+    fn regex_matches() {
+        let regex = parse("[a-c]*x|y.");
+
+        assert!(regex.matches("x"));
+        assert!(regex.matches("abcx"));
+        assert!(regex.matches("yž"));
+        assert!(!regex.matches("y"));
+        assert!(!regex.matches("dx"));
+    }
+}
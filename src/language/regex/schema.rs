@@ -0,0 +1,77 @@
+use super::Regex;
+use serde::Deserialize;
+
+/// The serialized form of a regex, which
+/// is checked before it becomes a `Regex`
+#[derive(Deserialize)]
+#[serde(rename = "Regex")]
+pub(super) enum RegexSchema {
+    EmptySet,
+    EmptyString,
+    Symbol(char),
+    Class(Vec<(char, char)>),
+    Concat(Box<RegexSchema>, Box<RegexSchema>),
+    Union(Box<RegexSchema>, Box<RegexSchema>),
+    KleeneStar(Box<RegexSchema>),
+}
+
+impl TryFrom<RegexSchema> for Regex {
+    type Error = &'static str;
+
+    fn try_from(schema: RegexSchema) -> Result<Self, Self::Error> {
+        let pair = |first: Box<RegexSchema>, second: Box<RegexSchema>| {
+            Ok::<_, &'static str>((
+                Box::new(Regex::try_from(*first)?),
+                Box::new(Regex::try_from(*second)?),
+            ))
+        };
+
+        Ok(match schema {
+            RegexSchema::EmptySet => Regex::EmptySet,
+            RegexSchema::EmptyString => Regex::EmptyString,
+            RegexSchema::Symbol(symbol) => Regex::Symbol(symbol),
+            RegexSchema::Class(ranges) => {
+                // the ranges have to be in the form `Regex::class` returns
+                let ordered = ranges.iter().all(|&(start, end)| start <= end);
+                if !ordered || Regex::class(&ranges) != Regex::Class(ranges.clone()) {
+                    return Err("Class ranges must be sorted, non-overlapping and non-adjacent");
+                }
+                Regex::Class(ranges)
+            }
+            RegexSchema::Concat(first, second) => {
+                let (first, second) = pair(first, second)?;
+                Regex::Concat(first, second)
+            }
+            RegexSchema::Union(first, second) => {
+                let (first, second) = pair(first, second)?;
+                Regex::Union(first, second)
+            }
+            RegexSchema::KleeneStar(inner) => Regex::KleeneStar(Box::new(Regex::try_from(*inner)?)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regex_serde_round_trip() {
+        let regex = Regex::parse("a[0-9x]*|()").unwrap();
+
+        let json = serde_json::to_string(&regex).unwrap();
+        assert_eq!(regex, serde_json::from_str::<Regex>(&json).unwrap());
+    }
+
+    #[test]
+    fn regex_serde_validation() {
+        let parse = |json: &str| serde_json::from_str::<Regex>(json);
+
+        assert!(parse(r#"{"Class":[["a","c"],["x","z"]]}"#).is_ok());
+        // unsorted, overlapping, adjacent and reversed ranges
+        assert!(parse(r#"{"Class":[["x","z"],["a","c"]]}"#).is_err());
+        assert!(parse(r#"{"Class":[["a","c"],["b","z"]]}"#).is_err());
+        assert!(parse(r#"{"Class":[["a","c"],["d","z"]]}"#).is_err());
+        assert!(parse(r#"{"KleeneStar":{"Class":[["c","a"]]}}"#).is_err());
+    }
+}
@@ -4,6 +4,8 @@ pub mod language;
 
 pub use self::automata::nfa;
 pub use self::automata::dfa;
+pub use self::automata::lazy_dfa;
 
 pub use nfa::Nfa;
 pub use dfa::Dfa;
+pub use lazy_dfa::LazyDfa;
@@ -1,36 +1,614 @@
-fn main() {
-    let dfa = fmsi::Dfa::from("a|(ab|b)*").unwrap();
-    let nfa = fmsi::Nfa::from("a|(ab|b)*").unwrap();
-
-    println!("------------------- Nfa -------------------");
-    println!("{:?}", nfa);
-    print!("\n\n\n");
-    println!("------------------- Non-minimized Dfa -------------------");
-    let non_min_dfa = nfa.to_dfa();
-    println!("{:?}", non_min_dfa);
-    print!("\n\n\n");
-    println!("------------------- Dfa -------------------");
-    println!("{:?}", dfa);
-    print!("\n\n\n");
-    println!("------------------- Regex -------------------");
-    let regex = dfa.to_regex();
-    println!("{}", regex);
-    print!("\n\n\n");
-
-    let nfa = fmsi::Nfa::from("a*b*").unwrap();
-    let dfa = fmsi::Dfa::from("a*b*").unwrap();
-    println!("------------------- Nfa -------------------");
-    println!("{:?}", nfa);
-    print!("\n\n\n");
-    println!("------------------- Non-minimized Dfa -------------------");
-    let non_min_dfa = nfa.to_dfa();
-    println!("{:?}", non_min_dfa);
-    print!("\n\n\n");
-    println!("------------------- Dfa -------------------");
-    println!("{:?}", dfa);
-    print!("\n\n\n");
-    println!("------------------- Regex -------------------");
-    let regex = dfa.to_regex();
-    println!("{}", regex);
-    print!("\n\n\n");
+use fmsi::automata::dot::{DotOptions, RankDirection};
+use fmsi::language::regex::CharClasses;
+use fmsi::language::Regex;
+use fmsi::{Dfa, Nfa};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: fmsi <command> [options] <arguments>
+
+Commands:
+  nfa <input>                 Prints the nfa of the input
+  dfa <input>                 Prints the dfa of the input, before minimization
+  minimize <input>            Prints the minimal dfa of the input
+  to-regex <input>            Prints a regex that matches the same strings as the input
+  match <input> [<text>...]   Checks if the input accepts every text, or every line
+                              of the standard input if there are no texts
+  equiv <input> <input>       Checks if the two inputs accept the same strings
+  dot <input>                 Prints the minimal dfa of the input in the dot format
+  grep <regex> [<file>...]    Prints the lines of the files, or of the standard
+                              input, that contain a match of the regex
+  help                        Prints this message
+
+An input is a regex, or `-f <file>` for an automaton file: a JFLAP file (.jff),
+an AT&T acceptor with code points as labels (.att), a JSON file (.json) or a
+transition table (any other).
+
+Regexes, also the one of grep, have `|` for union, `*` for repetition and
+parentheses, classes like `[a-z]` and `[^0-9]`, `.` for any char and `\\` to
+escape the next char. The empty regex and `()` match the empty string.
+Classes of more than 4096 chars, like `.` and most negated classes, only
+work in grep, as the other commands expand classes in to their chars.
+
+Options:
+  --format <text|json|dot>    Output format of nfa, dfa and minimize [default: text]
+  --rankdir <LR|TB|RL|BT>     Direction of the dot output [default: LR]
+  -x                          grep: only lines that match as a whole
+  -v                          grep: only lines that don't match
+  -n                          grep: prints line numbers
+  -c                          grep: prints the number of lines instead
+  --                          Treats the rest of the arguments as regexes or texts
+
+Exit status is 0 on success, 1 if match or equiv fail or grep finds
+no lines, and 2 on errors.";
+
+/// A positional argument, either given directly or read from a file
+#[derive(Debug, Clone, PartialEq)]
+enum Argument {
+    Text(String),
+    File(String),
+}
+
+/// An error of the command line, which is shown with
+/// the usage if the arguments are wrong
+#[derive(Debug, PartialEq)]
+enum Error {
+    Usage(String),
+    Run(String),
+}
+
+impl From<String> for Error {
+    fn from(error: String) -> Self {
+        Error::Run(error)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(error: &str) -> Self {
+        Error::Run(error.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+    Dot,
+}
+
+/// Flags of the grep command
+#[derive(Debug, Default, PartialEq)]
+struct GrepOptions {
+    whole_line: bool,
+    invert: bool,
+    line_numbers: bool,
+    count: bool,
+}
+
+#[derive(Debug, PartialEq)]
+struct Command {
+    name: String,
+    arguments: Vec<Argument>,
+    format: Option<Format>,
+    rank_direction: RankDirection,
+    grep: GrepOptions,
+}
+
+impl Command {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut args = args.iter();
+        let name = args.next().ok_or("Missing command")?.clone();
+
+        let mut command = Command {
+            name,
+            arguments: Vec::new(),
+            format: None,
+            rank_direction: RankDirection::default(),
+            grep: GrepOptions::default(),
+        };
+
+        let mut options_done = false;
+        while let Some(arg) = args.next() {
+            let mut value = |option: &str| {
+                args.next()
+                    .cloned()
+                    .ok_or(format!("Missing value for {}", option))
+            };
+
+            if options_done {
+                command.arguments.push(Argument::Text(arg.clone()));
+                continue;
+            }
+
+            match arg.as_str() {
+                "--" => options_done = true,
+                "-f" | "--file" => command.arguments.push(Argument::File(value(arg)?)),
+                "--format" => {
+                    command.format = Some(match value(arg)?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        "dot" => Format::Dot,
+                        format => return Err(format!("Unknown format {}", format)),
+                    })
+                }
+                "--rankdir" => {
+                    command.rank_direction = match value(arg)?.as_str() {
+                        "LR" => RankDirection::LeftToRight,
+                        "TB" => RankDirection::TopToBottom,
+                        "RL" => RankDirection::RightToLeft,
+                        "BT" => RankDirection::BottomToTop,
+                        direction => return Err(format!("Unknown rank direction {}", direction)),
+                    }
+                }
+                flags
+                    if flags.len() > 1
+                        && flags.starts_with('-')
+                        && flags[1..].chars().all(|flag| "xvnc".contains(flag)) =>
+                {
+                    for flag in flags[1..].chars() {
+                        match flag {
+                            'x' => command.grep.whole_line = true,
+                            'v' => command.grep.invert = true,
+                            'n' => command.grep.line_numbers = true,
+                            _ => command.grep.count = true,
+                        }
+                    }
+                }
+                option if option.starts_with('-') && option.len() > 1 => {
+                    return Err(format!("Unknown option {}", option))
+                }
+                _ => command.arguments.push(Argument::Text(arg.clone())),
+            }
+        }
+
+        Ok(command)
+    }
+
+    /// Returns the arguments, or an error if there aren't `count` of them
+    fn arguments(&self, count: usize) -> Result<&[Argument], Error> {
+        if self.arguments.len() != count {
+            return Err(Error::Usage(format!(
+                "{} takes {} argument{}",
+                self.name,
+                count,
+                if count == 1 { "" } else { "s" }
+            )));
+        }
+        Ok(&self.arguments)
+    }
+
+    fn dot_options(&self) -> DotOptions {
+        DotOptions {
+            rank_direction: self.rank_direction,
+            ..DotOptions::default()
+        }
+    }
+}
+
+/// Builds the nfa of a regex, or reads it from a file in the format
+/// given by the extension of the file
+fn load_nfa(argument: &Argument) -> Result<Nfa, String> {
+    let path = match argument {
+        Argument::Text(regex) => return Ok(Nfa::from(regex)?),
+        Argument::File(path) => path,
+    };
+
+    let input = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+    let extension = Path::new(path).extension().and_then(|e| e.to_str());
+
+    let nfa = match extension {
+        Some("jff") => Nfa::from_jflap(&input).map(|(nfa, _)| nfa),
+        Some("att") => Nfa::from_att(&input, None),
+        Some("json") => return load_json(&input).map_err(|e| format!("{}: {}", path, e)),
+        _ => Nfa::from_table(&input),
+    };
+
+    nfa.map_err(|error| format!("{}: {}", path, error))
+}
+
+#[cfg(feature = "cli-json")]
+fn load_json(input: &str) -> Result<Nfa, String> {
+    serde_json::from_str(input).map_err(|error| error.to_string())
+}
+
+#[cfg(not(feature = "cli-json"))]
+fn load_json(_: &str) -> Result<Nfa, String> {
+    Err("JSON needs fmsi to be built with the cli-json feature".to_string())
+}
+
+#[cfg(feature = "cli-json")]
+fn to_json<T: serde::Serialize>(automaton: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(automaton)
+        .map(|json| json + "\n")
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(not(feature = "cli-json"))]
+fn to_json<T>(_: &T) -> Result<String, String> {
+    Err("JSON needs fmsi to be built with the cli-json feature".to_string())
+}
+
+fn load_minimal_dfa(argument: &Argument) -> Result<Dfa, String> {
+    let mut dfa = load_nfa(argument)?.to_dfa();
+    dfa.minimize()?;
+    Ok(dfa)
+}
+
+/// Runs the command, writing it's output, and returns the exit status
+fn run(args: &[String], input: &mut dyn BufRead, output: &mut dyn Write) -> Result<u8, Error> {
+    let command = Command::parse(args).map_err(Error::Usage)?;
+    let io_error = |error: io::Error| error.to_string();
+
+    if command.format.is_some() && !matches!(command.name.as_str(), "nfa" | "dfa" | "minimize") {
+        return Err(Error::Usage(
+            "Option --format only works with nfa, dfa and minimize".to_string(),
+        ));
+    }
+    if command.name == "grep" {
+        return grep(&command, input, output);
+    }
+    if command.grep != GrepOptions::default() {
+        return Err(Error::Usage(
+            "Options -x, -v, -n and -c only work with grep".to_string(),
+        ));
+    }
+    let format = command.format.unwrap_or(Format::Text);
+
+    let text = match command.name.as_str() {
+        "nfa" => {
+            let nfa = load_nfa(&command.arguments(1)?[0])?;
+            match format {
+                Format::Text => nfa.to_string(),
+                Format::Json => to_json(&nfa)?,
+                Format::Dot => nfa.to_dot_with(&command.dot_options()),
+            }
+        }
+        "dfa" | "minimize" => {
+            let nfa = load_nfa(&command.arguments(1)?[0])?;
+            let mut dfa = nfa.to_dfa();
+            if command.name == "minimize" {
+                dfa.minimize()?;
+            }
+            match format {
+                Format::Text => dfa.to_string(),
+                Format::Json => to_json(&dfa)?,
+                Format::Dot => dfa.to_dot_with(&command.dot_options()),
+            }
+        }
+        "to-regex" => load_minimal_dfa(&command.arguments(1)?[0])?.to_regex() + "\n",
+        "dot" => load_minimal_dfa(&command.arguments(1)?[0])?.to_dot_with(&command.dot_options()),
+        "match" => {
+            let (pattern, texts) = command
+                .arguments
+                .split_first()
+                .ok_or(Error::Usage("match takes an input and texts".to_string()))?;
+            let dfa = load_minimal_dfa(pattern)?;
+
+            let mut texts: Vec<String> = texts
+                .iter()
+                .map(|text| match text {
+                    Argument::Text(text) => Ok(text.clone()),
+                    Argument::File(_) => {
+                        Err(Error::Usage("match takes texts, not files".to_string()))
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            if texts.is_empty() {
+                texts = input.lines().collect::<Result<_, _>>().map_err(io_error)?;
+            }
+
+            let mut status = 0;
+            for text in texts {
+                let accepted = dfa.run(&text).unwrap_or(false);
+                if !accepted {
+                    status = 1;
+                }
+                let result = if accepted { "accepted" } else { "rejected" };
+                writeln!(output, "{}\t{}", result, text).map_err(io_error)?;
+            }
+            return Ok(status);
+        }
+        "equiv" => {
+            let arguments = command.arguments(2)?;
+            let first = load_minimal_dfa(&arguments[0])?;
+            let second = load_minimal_dfa(&arguments[1])?;
+
+            if first.is_equivalent(&second) {
+                writeln!(output, "equivalent").map_err(io_error)?;
+                return Ok(0);
+            }
+            writeln!(output, "not equivalent").map_err(io_error)?;
+            return Ok(1);
+        }
+        "help" | "-h" | "--help" => format!("{}\n", USAGE),
+        name => return Err(Error::Usage(format!("Unknown command {}", name))),
+    };
+
+    write!(output, "{}", text).map_err(io_error)?;
+    Ok(0)
+}
+
+/// Prints the lines of the files, or of the input if there are
+/// no files, that are selected by the regex and the grep flags
+fn grep(command: &Command, input: &mut dyn BufRead, output: &mut dyn Write) -> Result<u8, Error> {
+    let options = &command.grep;
+    let io_error = |error: io::Error| error.to_string();
+
+    let (regex, paths) = match command.arguments.split_first() {
+        Some((Argument::Text(regex), paths)) => (regex, paths),
+        _ => return Err(Error::Usage("grep takes a regex and files".to_string())),
+    };
+    let paths: Vec<&str> = paths
+        .iter()
+        .map(|path| match path {
+            Argument::Text(path) => Ok(path.as_str()),
+            Argument::File(_) => Err(Error::Usage("grep takes files without -f".to_string())),
+        })
+        .collect::<Result<_, _>>()?;
+
+    // the automaton runs on the classes of chars the regex tells apart,
+    // so `.` and negated classes don't have to be expanded
+    let regex = Regex::parse(regex)?;
+    let classes = CharClasses::new(&regex);
+    let mut dfa = Nfa::from_regex(&classes.rewrite(&regex))?.to_dfa();
+    dfa.minimize()?;
+    let dense = dfa.compile();
+    let is_selected = |line: &str| {
+        let line = classes.map(line);
+        let found = if options.whole_line {
+            dense.run(&line)
+        } else {
+            dense.is_match(&line)
+        };
+        found != options.invert
+    };
+
+    let sources: Vec<Option<&str>> = if paths.is_empty() {
+        vec![None]
+    } else {
+        paths
+            .iter()
+            .map(|&path| Some(path).filter(|&path| path != "-"))
+            .collect()
+    };
+
+    let mut selected_any = false;
+    for source in sources.iter() {
+        let name = source.unwrap_or("(standard input)");
+        let mut file;
+        let reader: &mut dyn BufRead = match source {
+            Some(path) => {
+                let opened = fs::File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+                file = BufReader::new(opened);
+                &mut file
+            }
+            None => input,
+        };
+        // only prefix the lines with the file name when there are several files
+        let prefix = if sources.len() > 1 {
+            format!("{}:", name)
+        } else {
+            String::new()
+        };
+
+        let mut count = 0;
+        let mut number = 0;
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer).map_err(io_error)? == 0 {
+                break;
+            }
+            number += 1;
+
+            let line = String::from_utf8_lossy(&buffer);
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if !is_selected(line) {
+                continue;
+            }
+
+            count += 1;
+            if options.count {
+                continue;
+            }
+            if options.line_numbers {
+                writeln!(output, "{}{}:{}", prefix, number, line).map_err(io_error)?;
+            } else {
+                writeln!(output, "{}{}", prefix, line).map_err(io_error)?;
+            }
+        }
+
+        if options.count {
+            writeln!(output, "{}{}", prefix, count).map_err(io_error)?;
+        }
+        selected_any |= count > 0;
+    }
+
+    Ok(if selected_any { 0 } else { 1 })
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    match run(&args, &mut stdin.lock(), &mut stdout.lock()) {
+        Ok(status) => ExitCode::from(status),
+        Err(Error::Usage(error)) => {
+            eprintln!("fmsi: {}\n\n{}", error, USAGE);
+            ExitCode::from(2)
+        }
+        Err(Error::Run(error)) => {
+            eprintln!("fmsi: {}", error);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(args: &[&str], input: &str) -> Result<(u8, String), Error> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut output = Vec::new();
+        let status = run(&args, &mut input.as_bytes(), &mut output)?;
+        Ok((status, String::from_utf8(output).unwrap()))
+    }
+
+    #[test]
+    fn cli_parse() {
+        let args: Vec<String> = ["dot", "--rankdir", "TB", "-f", "a.jff", "--", "-a"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        assert_eq!(
+            Command {
+                name: "dot".to_string(),
+                arguments: vec![
+                    Argument::File("a.jff".to_string()),
+                    Argument::Text("-a".to_string())
+                ],
+                format: None,
+                rank_direction: RankDirection::TopToBottom,
+                grep: GrepOptions::default(),
+            },
+            Command::parse(&args).unwrap()
+        );
+        let is_usage = |args: &[&str]| matches!(run_with(args, ""), Err(Error::Usage(_)));
+        assert!(is_usage(&["nfa", "--format"]));
+        assert!(is_usage(&["nfa", "--verbose", "a"]));
+        assert!(is_usage(&["nfa"]));
+        assert!(is_usage(&["grow", "a"]));
+        assert!(is_usage(&[]));
+        assert!(is_usage(&["to-regex", "--format", "dot", "a"]));
+        assert!(is_usage(&["dot", "--format", "json", "a"]));
+        assert!(is_usage(&["grep", "--format", "text", "a"]));
+
+        // errors while running are shown without the usage
+        assert!(matches!(run_with(&["nfa", "a("], ""), Err(Error::Run(_))));
+        assert!(matches!(
+            run_with(&["nfa", "-f", "does-not-exist.jff"], ""),
+            Err(Error::Run(_))
+        ));
+    }
+
+    #[test]
+    fn cli_automata() {
+        let (status, output) = run_with(&["minimize", "a|b"], "").unwrap();
+        assert_eq!(0, status);
+        assert_eq!(Dfa::from("a|b").unwrap().to_string(), output);
+
+        let (_, output) = run_with(&["nfa", "--format", "dot", "a*"], "").unwrap();
+        assert!(output.starts_with("digraph {"));
+
+        let (_, output) = run_with(&["dot", "--rankdir", "BT", "ab"], "").unwrap();
+        assert!(output.contains("rankdir=BT;"));
+
+        let (_, output) = run_with(&["to-regex", "ab*"], "").unwrap();
+        assert_eq!(Dfa::from("ab*").unwrap().to_regex() + "\n", output);
+
+        // the printed regex is read back as the same language
+        for expression in ["a", "ab|b", "(a|\\*)*b"] {
+            let (_, regex) = run_with(&["to-regex", expression], "").unwrap();
+            let (status, _) = run_with(&["equiv", regex.trim_end(), expression], "").unwrap();
+            assert_eq!(0, status, "{}", regex);
+        }
+
+        let json = run_with(&["dfa", "--format", "json", "ab"], "");
+        assert_eq!(cfg!(feature = "cli-json"), json.is_ok());
+    }
+
+    #[test]
+    fn cli_match_and_equiv() {
+        let (status, output) = run_with(&["match", "(ab)*", "abab", ""], "").unwrap();
+        assert_eq!(0, status);
+        assert_eq!("accepted\tabab\naccepted\t\n", output);
+
+        let (status, output) = run_with(&["match", "(ab)*"], "ab\naba\n").unwrap();
+        assert_eq!(1, status);
+        assert_eq!("accepted\tab\nrejected\taba\n", output);
+
+        let (status, _) = run_with(&["equiv", "(a|b)*", "(a*b*)*"], "").unwrap();
+        assert_eq!(0, status);
+        let (status, output) = run_with(&["equiv", "(a|b)*", "a*b*"], "").unwrap();
+        assert_eq!((1, "not equivalent\n"), (status, output.as_str()));
+    }
+
+    #[test]
+    fn cli_grep() {
+        let text = "abba\nxaby\nba\r\nab\n";
+
+        let (status, output) = run_with(&["grep", "ab*"], text).unwrap();
+        assert_eq!((0, "abba\nxaby\nba\nab\n"), (status, output.as_str()));
+
+        let (_, output) = run_with(&["grep", "-x", "ab*"], text).unwrap();
+        assert_eq!("ab\n", output);
+
+        let (_, output) = run_with(&["grep", "-n", "-x", "-v", "ab*"], text).unwrap();
+        assert_eq!("1:abba\n2:xaby\n3:ba\n", output);
+
+        let (_, output) = run_with(&["grep", "-xc", "(a|b)*"], text).unwrap();
+        assert_eq!("3\n", output);
+
+        let (status, output) = run_with(&["grep", "-c", "c"], text).unwrap();
+        assert_eq!((1, "0\n"), (status, output.as_str()));
+
+        // the empty regex matches every line, like in other greps
+        let (status, output) = run_with(&["grep", ""], "a\n\nb\n").unwrap();
+        assert_eq!((0, "a\n\nb\n"), (status, output.as_str()));
+        let (_, output) = run_with(&["grep", "-x", ""], "a\n\nb\n").unwrap();
+        assert_eq!("\n", output);
+
+        // `.` and negated classes match chars the regex doesn't mention
+        let (_, output) = run_with(&["grep", "a.b"], "a-b\naž b\nab\nxaβbx\n").unwrap();
+        assert_eq!("a-b\nxaβbx\n", output);
+        let (_, output) = run_with(&["grep", "-x", "[^0-9]*"], "ab\na1\n\nž!\n").unwrap();
+        assert_eq!("ab\n\nž!\n", output);
+        let (_, output) = run_with(&["grep", "-c", "[^0-9]"], "12\n1a2\n\n").unwrap();
+        assert_eq!("1\n", output);
+
+        assert!(run_with(&["grep"], text).is_err());
+        assert!(run_with(&["grep", "a", "does-not-exist.txt"], text).is_err());
+        assert!(run_with(&["minimize", "-c", "a"], text).is_err());
+    }
+
+    #[test]
+    fn cli_grep_files() {
+        let directory = std::env::temp_dir();
+        let first = directory.join(format!("fmsi-grep-first-{}.txt", std::process::id()));
+        let second = directory.join(format!("fmsi-grep-second-{}.txt", std::process::id()));
+        fs::write(&first, "one\ntwo\n").unwrap();
+        fs::write(&second, "three\n").unwrap();
+        let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+
+        let (_, output) = run_with(&["grep", "-n", "o", first, second], "").unwrap();
+        assert_eq!(format!("{}:1:one\n{}:2:two\n", first, first), output);
+
+        let (_, output) = run_with(&["grep", "-c", "e", first, "-", second], "e\n").unwrap();
+        assert_eq!(
+            format!("{}:1\n(standard input):1\n{}:1\n", first, second),
+            output
+        );
+
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn cli_files() {
+        let path = std::env::temp_dir().join(format!("fmsi-cli-{}.txt", std::process::id()));
+        fs::write(&path, Nfa::from("a|(ab|b)*").unwrap().to_string()).unwrap();
+        let path = path.to_str().unwrap();
+
+        let (status, _) = run_with(&["equiv", "-f", path, "a|(ab|b)*"], "").unwrap();
+        assert_eq!(0, status);
+        assert!(run_with(&["nfa", "-f", "does-not-exist.jff"], "").is_err());
+
+        fs::remove_file(path).unwrap();
+    }
 }
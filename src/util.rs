@@ -1,6 +1,8 @@
-use crate::language::EMPTY_STRING;
+use crate::language::Symbol;
 use crate::nfa::*;
 use std::collections::vec_deque::VecDeque;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
 /// Checks the fiven regular expression for correctness
 ///
@@ -52,36 +54,25 @@ pub fn check_for_correctness(regex: &str) -> Result<(), &'static str> {
 ///
 /// assert_eq!(vec![0, 1, 2, 4, 5, 6, 7, 8, 12, 13], result);
 /// ```
-pub fn state_epsilon_clojure(nfa: &Nfa, state: u32) -> Vec<u32> {
+pub fn state_epsilon_clojure<S: Symbol>(nfa: &Nfa<S>, state: u32) -> Vec<u32> {
     let mut clojure = vec![state];
+    let mut visited: HashSet<u32> = HashSet::from([state]);
     let mut queue: VecDeque<u32> = VecDeque::new();
 
     // add the given state to the queue
     queue.push_back(state);
 
-    loop {
-        // grab the first element from the queue
-        let current = queue.pop_front();
-
-        match current {
-            Some(current) => {
-                if let Some(destinations) = nfa.get_transition((current, EMPTY_STRING)) {
-                    for &state in destinations {
-                        // if there are epsilon transitions for this state
-                        // add them to the clojure
-                        if !clojure.contains(&state) {
-                            clojure.push(state);
-                        }
-
-                        // if they aren't already in the queue, add them
-                        if !queue.contains(&state) {
-                            queue.push_back(state);
-                        }
-                    }
+    // grab the first element from the queue until there are none left
+    while let Some(current) = queue.pop_front() {
+        if let Some(destinations) = nfa.get_transition((current, None)) {
+            for &state in destinations {
+                // if there are epsilon transitions to new states,
+                // add them to the clojure and the queue
+                if visited.insert(state) {
+                    clojure.push(state);
+                    queue.push_back(state);
                 }
             }
-            // if there are no elements left, the algorithm is done
-            None => break,
         }
     }
     clojure.sort();
@@ -101,15 +92,16 @@ pub fn state_epsilon_clojure(nfa: &Nfa, state: u32) -> Vec<u32> {
 ///
 /// assert_eq!(vec![0, 1, 2, 4, 5, 6, 7, 8, 12, 13], result);
 /// ```
-pub fn set_epsilon_clojure(nfa: &Nfa, set: &[u32]) -> Vec<u32> {
-    let mut clojure: Vec<u32> = Vec::new();
+pub fn set_epsilon_clojure<S: Symbol>(nfa: &Nfa<S>, set: &[u32]) -> Vec<u32> {
+    let mut clojure: HashSet<u32> = HashSet::new();
 
     for state in set {
-        let mut set_clojure = state_epsilon_clojure(nfa, *state);
-        set_clojure.retain(|&element| !clojure.contains(&element));
-        clojure.extend(set_clojure);
+        if !clojure.contains(state) {
+            clojure.extend(state_epsilon_clojure(nfa, *state));
+        }
     }
 
+    let mut clojure: Vec<u32> = clojure.into_iter().collect();
     clojure.sort();
     clojure
 }
@@ -127,11 +119,11 @@ pub fn set_epsilon_clojure(nfa: &Nfa, set: &[u32]) -> Vec<u32> {
 ///
 /// assert_eq!(vec![11, 14], result);
 /// ```
-pub fn set_transitions(nfa: &Nfa, set: &[u32], symbol: char) -> Vec<u32> {
+pub fn set_transitions<S: Symbol>(nfa: &Nfa<S>, set: &[u32], symbol: S) -> Vec<u32> {
     let mut target: Vec<u32> = Vec::new();
 
     for state in set {
-        if let Some(destinations) = nfa.get_transition((*state, symbol)) {
+        if let Some(destinations) = nfa.get_transition((*state, Some(symbol.clone()))) {
             let mut to_add: Vec<u32> = destinations
                 .iter()
                 .filter(|&d| !target.contains(d))
@@ -146,28 +138,44 @@ pub fn set_transitions(nfa: &Nfa, set: &[u32], symbol: char) -> Vec<u32> {
     target
 }
 
+/// Splits the given symbols in to groups of symbols with the same key.
+/// Every group is sorted and the groups are ordered by their first symbol.
+///
+/// # Example
+/// ```rust
+/// use fmsi::util::group_symbols;
+///
+/// let groups = group_symbols(&['d', 'a', 'c', 'b'], |s| *s == 'b' || *s == 'd');
+///
+/// assert_eq!(vec![vec!['a', 'c'], vec!['b', 'd']], groups);
+/// ```
+pub fn group_symbols<S, K, F>(symbols: &[S], key: F) -> Vec<Vec<S>>
+where
+    S: Symbol,
+    K: Eq + Hash,
+    F: Fn(&S) -> K,
+{
+    let mut symbols = symbols.to_vec();
+    symbols.sort();
+
+    let mut lookup_table: HashMap<K, usize> = HashMap::new();
+    let mut groups: Vec<Vec<S>> = Vec::new();
+
+    for symbol in symbols {
+        let index = *lookup_table.entry(key(&symbol)).or_insert(groups.len());
+        match groups.get_mut(index) {
+            Some(group) => group.push(symbol),
+            None => groups.push(vec![symbol]),
+        }
+    }
+
+    groups
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn util_power_set() {
-        let result = create_power_set(&[0, 1, 2]);
-        assert_eq!(
-            vec![
-                vec![0],
-                vec![0, 1],
-                vec![0, 1, 2],
-                vec![1],
-                vec![1, 2],
-                vec![2]
-            ],
-            result
-        );
-
-        println!("{:?}", result);
-    }
-
     #[test]
     fn checking_for_correctness() {
         assert!(check_for_correctness("(*ab)").is_err());